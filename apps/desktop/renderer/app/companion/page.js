@@ -1,5 +1,6 @@
 'use client';
 import { useState, useEffect } from 'react';
+import { tauriBridge } from '../../lib/tauri-bridge';
 
 // Ear shapes for each pal — white silhouettes peeking from bottom
 const PAL_EARS = {
@@ -54,27 +55,13 @@ export default function Companion() {
   const [palId, setPalId] = useState('cat');
 
   useEffect(() => {
-    // Read selected pal from localStorage
-    try {
-      const saved = localStorage.getItem('meow-pal');
-      if (saved !== null) {
-        const idx = parseInt(saved, 10);
-        if (PAL_IDS[idx]) setPalId(PAL_IDS[idx]);
-      }
-    } catch {}
-
-    // Poll for changes (since companion is a separate window)
-    const interval = setInterval(() => {
-      try {
-        const saved = localStorage.getItem('meow-pal');
-        if (saved !== null) {
-          const idx = parseInt(saved, 10);
-          if (PAL_IDS[idx]) setPalId(PAL_IDS[idx]);
-        }
-      } catch {}
-    }, 2000);
-
-    return () => clearInterval(interval);
+    // Follow the selected pal via the shared settings store
+    const apply = (s) => {
+      if (s && PAL_IDS[s.pal]) setPalId(PAL_IDS[s.pal]);
+    };
+    tauriBridge.getSettings().then(apply);
+    const unlisten = tauriBridge.onSettingsChanged(apply);
+    return () => { unlisten.then(fn => fn && fn()); };
   }, []);
 
   return (
@@ -21,13 +21,11 @@ export default function EyeBreakPage() {
     setStrict(s);
   }, []);
 
-  // Load pal from localStorage
+  // Load pal from settings
   useEffect(() => {
-    const PALS = ['🐱', '🦊', '🦉', '🐼', '🐰'];
-    try {
-      const saved = localStorage.getItem('meow-pal');
-      if (saved) setPalIcon(PALS[parseInt(saved, 10)] || '👀');
-    } catch {}
+    Promise.all([tauriBridge.getSettings(), tauriBridge.listPals()]).then(([s, pals]) => {
+      if (s) setPalIcon(pals[s.pal]?.icon || '👀');
+    });
   }, []);
 
   // Countdown timer
@@ -1,17 +1,64 @@
 'use client';
-import { useState, useEffect } from 'react';
+import { useState, useEffect, useCallback, useRef } from 'react';
 import { tauriBridge } from '../../lib/tauri-bridge';
+import TaskList from '../../components/TaskList';
+
+const PAGE_SIZE = 100;
 
 export default function HistoryPage() {
   const [history, setHistory] = useState([]);
+  const [total, setTotal] = useState(0);
+  const [tab, setTab] = useState('sessions');
+  const shownRef = useRef(PAGE_SIZE);
 
-  useEffect(() => {
-    tauriBridge.getHistory().then(setHistory);
+  // Reload the pages already on screen
+  const reload = useCallback(async () => {
+    const page = await tauriBridge.listSessions(0, shownRef.current);
+    setHistory(page.sessions);
+    setTotal(page.total);
   }, []);
 
+  const loadMore = async () => {
+    const page = await tauriBridge.listSessions(history.length, PAGE_SIZE);
+    shownRef.current = history.length + PAGE_SIZE;
+    setHistory(prev => [...prev, ...page.sessions]);
+    setTotal(page.total);
+  };
+
+  useEffect(() => {
+    reload();
+    // Renaming a task renames its sessions too
+    const unlisten = tauriBridge.onTasksChanged(reload);
+    return () => { unlisten.then(fn => fn && fn()); };
+  }, [reload]);
+
+  const [showExport, setShowExport] = useState(false);
+  const [importResult, setImportResult] = useState(null);
+
   const clearHistory = async () => {
     await tauriBridge.clearHistory();
     setHistory([]);
+    setTotal(0);
+  };
+
+  const importHistory = async () => {
+    try {
+      const report = await tauriBridge.importHistory();
+      if (!report) return;
+      setImportResult(report);
+      await reload();
+    } catch (err) {
+      setImportResult({ error: String(err) });
+    }
+  };
+
+  const exportHistory = async (format) => {
+    setShowExport(false);
+    try {
+      await tauriBridge.exportHistory(format);
+    } catch (err) {
+      console.error('Export failed', err);
+    }
   };
 
   const formatType = (type) => {
@@ -29,20 +76,78 @@ export default function HistoryPage() {
     <div className="min-h-screen p-4" style={{ background: '#1c1c1e' }}>
       {/* Header */}
       <div className="flex items-center justify-between mb-4">
-        <h1 className="text-base font-semibold text-text-primary">Session History</h1>
-        {history.length > 0 && (
+        <div className="flex rounded-lg overflow-hidden" style={{ background: '#2c2c2e' }}>
+          {[['sessions', 'Sessions'], ['tasks', 'Tasks']].map(([id, label]) => (
+            <button
+              key={id}
+              onClick={() => setTab(id)}
+              className={`text-[13px] font-semibold px-3 py-1 transition-colors ${
+                tab === id ? 'bg-white/15 text-text-primary' : 'text-text-muted'
+              }`}
+            >
+              {label}
+            </button>
+          ))}
+        </div>
+        <div className={`flex items-center gap-1.5 ${tab === 'sessions' ? '' : 'hidden'}`}>
           <button
-            onClick={clearHistory}
-            className="text-[11px] px-3 py-1.5 rounded-lg transition-colors"
-            style={{ background: '#2c2c2e', color: '#ff6b6b' }}
+            onClick={importHistory}
+            className="text-[11px] px-3 py-1.5 rounded-lg transition-colors text-text-primary"
+            style={{ background: '#2c2c2e' }}
           >
-            Clear All
+            Import
           </button>
-        )}
+          {history.length > 0 && (
+            <div className="relative">
+              <button
+                onClick={() => setShowExport(!showExport)}
+                className="text-[11px] px-3 py-1.5 rounded-lg transition-colors text-text-primary"
+                style={{ background: '#2c2c2e' }}
+              >
+                Export
+              </button>
+              {showExport && (
+                <div
+                  className="absolute right-0 mt-1 flex flex-col py-1 z-10"
+                  style={{ background: '#2c2c2e', borderRadius: 10, minWidth: 120 }}
+                >
+                  {[['csv', 'CSV'], ['json', 'JSON'], ['ics', 'Calendar (.ics)']].map(([format, label]) => (
+                    <button
+                      key={format}
+                      onClick={() => exportHistory(format)}
+                      className="text-[11px] text-left px-3 py-1.5 text-text-primary hover:bg-white/10 transition-colors"
+                    >
+                      {label}
+                    </button>
+                  ))}
+                </div>
+              )}
+            </div>
+          )}
+          {history.length > 0 && (
+            <button
+              onClick={clearHistory}
+              className="text-[11px] px-3 py-1.5 rounded-lg transition-colors"
+              style={{ background: '#2c2c2e', color: '#ff6b6b' }}
+            >
+              Clear All
+            </button>
+          )}
+        </div>
       </div>
 
+      {importResult && (
+        <p className="text-[11px] text-text-muted mb-3">
+          {importResult.error
+            ? `Import failed: ${importResult.error}`
+            : `Imported ${importResult.imported}, skipped ${importResult.skipped} duplicate${importResult.skipped === 1 ? '' : 's'}, ${importResult.malformed} unreadable.`}
+        </p>
+      )}
+
       {/* List */}
-      {history.length === 0 ? (
+      {tab === 'tasks' ? (
+        <TaskList />
+      ) : history.length === 0 ? (
         <div className="text-center mt-16">
           <img src="/image.png" alt="meow" className="w-10 h-10 rounded-xl mx-auto mb-3" style={{ background: '#2c2c2e', padding: 4 }} />
           <p className="text-text-muted text-sm">No sessions yet.</p>
@@ -69,11 +174,23 @@ export default function HistoryPage() {
                 </div>
                 <div className="text-right shrink-0">
                   <div className="text-[13px] font-semibold text-accent-light">{h.duration} min</div>
+                  {h.idleSeconds >= 60 && (
+                    <div className="text-[10px] text-text-muted">{Math.round(h.idleSeconds / 60)} min away</div>
+                  )}
                   <div className="text-[10px] text-text-muted">{date} {time}</div>
                 </div>
               </div>
             );
           })}
+          {history.length < total && (
+            <button
+              onClick={loadMore}
+              className="text-[11px] py-2 rounded-lg text-text-secondary hover:text-text-primary transition-colors"
+              style={{ background: '#2c2c2e' }}
+            >
+              Show older ({total - history.length} more)
+            </button>
+          )}
         </div>
       )}
     </div>
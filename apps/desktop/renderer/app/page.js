@@ -5,15 +5,14 @@ import { useTimer } from '../hooks/useTimer';
 import { useAudio } from '../hooks/useAudio';
 import { useEyeBreak } from '../hooks/useEyeBreak';
 import { tauriBridge } from '../lib/tauri-bridge';
+import ShortcutSettings from '../components/ShortcutSettings';
+import ApiSettings from '../components/ApiSettings';
+import WebhookSettings from '../components/WebhookSettings';
+import HookSettings from '../components/HookSettings';
+import TaskPicker from '../components/TaskPicker';
 
-// ── Focus Pals ──
-const PALS = [
-  { id: 'cat', name: 'Luna', icon: '\u{1F431}' },
-  { id: 'fox', name: 'Rusty', icon: '\u{1F98A}' },
-  { id: 'owl', name: 'Hoot', icon: '\u{1F989}' },
-  { id: 'panda', name: 'Bamboo', icon: '\u{1F43C}' },
-  { id: 'bunny', name: 'Clover', icon: '\u{1F430}' },
-];
+// Shown until the focus pals have loaded
+const NO_PAL = { id: 'none', name: '', icon: '' };
 
 export default function Home() {
   const [expanded, setExpanded] = useState(false);
@@ -23,8 +22,15 @@ export default function Home() {
   const [showTimerPicker, setShowTimerPicker] = useState(false);
   const [showAbout, setShowAbout] = useState(false);
   const [showEyeBreak, setShowEyeBreak] = useState(false);
+  const [showTasks, setShowTasks] = useState(false);
   const [taskName, setTaskName] = useState('');
+  // Set when the task was picked from the task list; a typed name is
+  // matched up (or added) by name when the session is saved
+  const [taskId, setTaskId] = useState(null);
   const [selectedPal, setSelectedPal] = useState(0);
+  // Focus pals come from Rust, which the tray shares
+  const [pals, setPals] = useState([]);
+  const pal = pals[selectedPal] || NO_PAL;
   const [timerMinutes, setTimerMinutes] = useState(25);
   const [pomodoroMode, setPomodoroMode] = useState(false);
   const [pomodoroSettings, setPomodoroSettings] = useState({
@@ -32,38 +38,71 @@ export default function Home() {
     shortBreakMinutes: 5,
     longBreakMinutes: 15,
   });
+  // 'primary' | 'cursor' | { named: '<monitor name>' }
+  const [pillMonitor, setPillMonitor] = useState('primary');
+  const [monitors, setMonitors] = useState([]);
+  const [shortcuts, setShortcuts] = useState({});
+  const [api, setApi] = useState(null);
+  const [webhooks, setWebhooks] = useState([]);
+  const [hooks, setHooks] = useState(null);
+  const [focusDnd, setFocusDnd] = useState(false);
+  const [idleSettings, setIdleSettings] = useState(null);
   const containerRef = useRef(null);
   const isCollapsingRef = useRef(false);
   const lastHeightRef = useRef(0);
 
+  // Settings live in Rust; mirror them here and push local edits back.
+  const syncedSettingsRef = useRef(null);
   useEffect(() => {
-    try {
-      const saved = localStorage.getItem('meow-minutes');
-      if (saved) setTimerMinutes(parseInt(saved, 10));
-      const savedPal = localStorage.getItem('meow-pal');
-      if (savedPal) setSelectedPal(parseInt(savedPal, 10));
-      const savedPomo = localStorage.getItem('meow-pomodoro');
-      if (savedPomo) setPomodoroMode(savedPomo === 'true');
-      const savedPomoSettings = localStorage.getItem('meow-pomodoro-settings');
-      if (savedPomoSettings) setPomodoroSettings(JSON.parse(savedPomoSettings));
-    } catch {}
+    const apply = (s) => {
+      if (!s) return;
+      const { minutes, pomodoroMode, workMinutes, shortBreakMinutes, longBreakMinutes } = s.timer || {};
+      syncedSettingsRef.current = JSON.stringify({
+        pal: s.pal, minutes, pomodoroMode, workMinutes, shortBreakMinutes, longBreakMinutes,
+      });
+      if (minutes) setTimerMinutes(minutes);
+      if (typeof s.pal === 'number') setSelectedPal(s.pal);
+      if (typeof pomodoroMode === 'boolean') setPomodoroMode(pomodoroMode);
+      if (workMinutes) setPomodoroSettings({ workMinutes, shortBreakMinutes, longBreakMinutes });
+      if (s.pillMonitor) setPillMonitor(s.pillMonitor);
+      if (s.shortcuts) setShortcuts(s.shortcuts);
+      if (s.api) setApi(s.api);
+      if (s.webhooks) setWebhooks(s.webhooks);
+      if (s.hooks) setHooks(s.hooks);
+      if (typeof s.focusDnd === 'boolean') setFocusDnd(s.focusDnd);
+      if (s.idle) setIdleSettings(s.idle);
+    };
+    tauriBridge.getSettings().then(apply);
+    tauriBridge.listPals().then(setPals);
+    const unlisten = tauriBridge.onSettingsChanged(apply);
+    return () => { unlisten.then(fn => fn && fn()); };
   }, []);
 
   useEffect(() => {
-    try { localStorage.setItem('meow-minutes', String(timerMinutes)); } catch {}
-  }, [timerMinutes]);
-
-  useEffect(() => {
-    try { localStorage.setItem('meow-pomodoro', String(pomodoroMode)); } catch {}
-  }, [pomodoroMode]);
+    // Skip until the stored settings have loaded, and skip echoes of them
+    if (syncedSettingsRef.current === null) return;
+    const { workMinutes, shortBreakMinutes, longBreakMinutes } = pomodoroSettings;
+    const current = JSON.stringify({
+      pal: selectedPal, minutes: timerMinutes, pomodoroMode, workMinutes, shortBreakMinutes, longBreakMinutes,
+    });
+    if (current === syncedSettingsRef.current) return;
+    syncedSettingsRef.current = current;
+    tauriBridge.updateSettings({
+      pal: selectedPal,
+      timer: { minutes: timerMinutes, pomodoroMode, workMinutes, shortBreakMinutes, longBreakMinutes },
+    });
+  }, [selectedPal, timerMinutes, pomodoroMode, pomodoroSettings]);
 
+  // Refresh the monitor list whenever settings open (displays come and go)
   useEffect(() => {
-    try { localStorage.setItem('meow-pomodoro-settings', JSON.stringify(pomodoroSettings)); } catch {}
-  }, [pomodoroSettings]);
+    if (showSettings) tauriBridge.listMonitors().then(setMonitors);
+  }, [showSettings]);
 
-  useEffect(() => {
-    try { localStorage.setItem('meow-pal', String(selectedPal)); } catch {}
-  }, [selectedPal]);
+  const choosePillMonitor = (value) => {
+    const choice = value.startsWith('named:') ? { named: value.slice(6) } : value;
+    setPillMonitor(choice);
+    tauriBridge.updateSettings({ pillMonitor: choice });
+  };
 
   useEffect(() => {
     if (!containerRef.current || typeof window === 'undefined') return;
@@ -139,19 +178,21 @@ export default function Home() {
   const handleTimerComplete = useCallback((...args) => {
     playChime();
     if (pomodoroMode) {
-      const [mode, sessions] = args;
-      saveHistory({ type: mode, duration: mode === 'work' ? pomodoroSettings.workMinutes : mode === 'shortBreak' ? pomodoroSettings.shortBreakMinutes : pomodoroSettings.longBreakMinutes, task: taskName, pal: PALS[selectedPal].icon, date: new Date().toISOString() });
+      // `minutes` is the phase's real length, which a CLI/API start can override
+      const [mode, sessions, idleSeconds = 0, minutes] = args;
+      const configured = mode === 'work' ? pomodoroSettings.workMinutes : mode === 'shortBreak' ? pomodoroSettings.shortBreakMinutes : pomodoroSettings.longBreakMinutes;
+      saveHistory({ type: mode, duration: minutes ?? configured, task: taskName, taskId, pal: pal.icon, date: new Date().toISOString(), completed: true, idleSeconds });
       if (mode === 'work') {
         tauriBridge.showNotification('Focus complete!', `${sessions} session${sessions > 1 ? 's' : ''} done. Time for a break.`);
       } else {
         tauriBridge.showNotification('Break over!', 'Ready to focus again?');
       }
     } else {
-      const sessions = args[0];
-      saveHistory({ type: 'focus', duration: timerMinutes, task: taskName, pal: PALS[selectedPal].icon, date: new Date().toISOString() });
+      const [sessions, idleSeconds = 0, minutes] = args;
+      saveHistory({ type: 'focus', duration: minutes ?? timerMinutes, task: taskName, taskId, pal: pal.icon, date: new Date().toISOString(), completed: true, idleSeconds });
       tauriBridge.showNotification('Timer done!', `${sessions} session${sessions > 1 ? 's' : ''} completed.`);
     }
-  }, [playChime, pomodoroMode, pomodoroSettings, timerMinutes, taskName, selectedPal, saveHistory]);
+  }, [playChime, pomodoroMode, pomodoroSettings, timerMinutes, taskName, taskId, pal, saveHistory]);
 
   const timer = useTimer({
     minutes: timerMinutes,
@@ -202,22 +243,57 @@ export default function Home() {
       unlisteners.push(await tauriBridge.onEyeBreakNow(() => {
         eyeBreak.startBreak();
       }));
+      unlisteners.push(await tauriBridge.onTrayStartFocus(({ task }) => {
+        if (task) {
+          setTaskName(task);
+          setTaskId(null);
+        }
+      }));
     };
     setup();
     return () => { unlisteners.forEach(fn => fn && fn()); };
   }, [eyeBreak.dismissBreak, eyeBreak.startBreak]);
 
+  // Tell Rust when the cursor enters or leaves the pill, for hover-expand
+  // and auto-collapse where it can't track the cursor itself
+  useEffect(() => {
+    if (typeof window === 'undefined') return;
+    const root = document.documentElement;
+    const onEnter = () => tauriBridge.setPointerInside(true);
+    const onLeave = () => tauriBridge.setPointerInside(false);
+    root.addEventListener('mouseenter', onEnter);
+    root.addEventListener('mouseleave', onLeave);
+    return () => {
+      root.removeEventListener('mouseenter', onEnter);
+      root.removeEventListener('mouseleave', onLeave);
+    };
+  }, []);
+
+  // `meow start --task <name>` from the CLI
+  useEffect(() => {
+    const unlisten = tauriBridge.onSetTask((name) => {
+      setTaskName(name);
+      setTaskId(null);
+    });
+    return () => { unlisten.then(fn => fn && fn()); };
+  }, []);
+
+  // "Mute sounds" global shortcut
+  useEffect(() => {
+    const unlisten = tauriBridge.onToggleMute(() => {
+      if (audio.isPaused) audio.resumeAll();
+      else audio.pauseAll();
+    });
+    return () => { unlisten.then(fn => fn && fn()); };
+  }, [audio.isPaused, audio.pauseAll, audio.resumeAll]);
+
   const activeCount = Object.keys(audio.activeSounds).length;
-  const pal = PALS[selectedPal];
 
   useEffect(() => {
     if (typeof window === 'undefined') return;
     const unlisteners = [];
 
     const setup = async () => {
-      unlisteners.push(await tauriBridge.onStartFocus(() => { timer.start(); setExpanded(true); }));
-      unlisteners.push(await tauriBridge.onPause(() => timer.pause()));
-      unlisteners.push(await tauriBridge.onReset(() => timer.reset()));
       unlisteners.push(await tauriBridge.on('open-about', () => {
         setExpanded(true);
         setShowAbout(true);
@@ -237,6 +313,7 @@ export default function Home() {
         setShowSounds(false);
         setShowPalPicker(false);
         setShowTimerPicker(false);
+        setShowTasks(false);
         setShowAbout(false);
         setShowEyeBreak(false);
         setTimeout(() => { isCollapsingRef.current = false; }, 300);
@@ -247,7 +324,7 @@ export default function Home() {
     return () => {
       unlisteners.forEach((fn) => fn && fn());
     };
-  }, [timer.start, timer.pause, timer.reset]);
+  }, []);
 
   const timerDisplay = timer.display;
 
@@ -333,7 +410,7 @@ export default function Home() {
               {/* Settings & About */}
               <div className="flex items-center justify-end gap-1 px-4 pt-2.5">
                 <button
-                  onClick={() => { setShowAbout(!showAbout); setShowSettings(false); setShowSounds(false); setShowPalPicker(false); setShowTimerPicker(false); setShowEyeBreak(false); }}
+                  onClick={() => { setShowAbout(!showAbout); setShowSettings(false); setShowSounds(false); setShowPalPicker(false); setShowTimerPicker(false); setShowTasks(false); setShowEyeBreak(false); }}
                   className="no-drag w-7 h-7 rounded-full flex items-center justify-center text-text-muted hover:text-text-secondary transition-colors cursor-pointer"
                 >
                   <svg width="15" height="15" viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="1.5">
@@ -344,7 +421,7 @@ export default function Home() {
                 </button>
                 <button
                   data-tour="settings-btn"
-                  onClick={() => { setShowSettings(!showSettings); setShowSounds(false); setShowPalPicker(false); setShowTimerPicker(false); setShowAbout(false); setShowEyeBreak(false); }}
+                  onClick={() => { setShowSettings(!showSettings); setShowSounds(false); setShowPalPicker(false); setShowTimerPicker(false); setShowTasks(false); setShowAbout(false); setShowEyeBreak(false); }}
                   className="no-drag w-7 h-7 rounded-full flex items-center justify-center text-text-muted hover:text-text-secondary transition-colors cursor-pointer"
                 >
                   <svg width="15" height="15" viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="1.5">
@@ -365,6 +442,7 @@ export default function Home() {
                     onClick={() => {
                       if (!timer.isRunning) {
                         setShowTimerPicker(!showTimerPicker);
+                        setShowTasks(false);
                         setShowSounds(false);
                         setShowPalPicker(false);
                         setShowEyeBreak(false);
@@ -397,9 +475,16 @@ export default function Home() {
                       <input
                         type="text"
                         value={taskName}
-                        onChange={(e) => setTaskName(e.target.value)}
+                        onChange={(e) => {
+                          setTaskName(e.target.value);
+                          setTaskId(null);
+                        }}
                         placeholder="Task (optional)"
                         onMouseDown={() => tauriBridge.focusWindow()}
+                        onFocus={() => {
+                          setShowTasks(true);
+                          setShowTimerPicker(false);
+                        }}
                         className="no-drag w-full bg-transparent text-center text-text-muted text-sm outline-none placeholder:text-text-muted"
                       />
                     )}
@@ -437,13 +522,39 @@ export default function Home() {
                     )}
                   </div>
                 </div>
+
+                {/* Back from an idle pause: keep the time away or not */}
+                {timer.idleAway !== null && (
+                  <div
+                    className="mt-2 px-4 py-2 flex items-center gap-2"
+                    style={{ background: '#2c2c2e', borderRadius: 16 }}
+                  >
+                    <span className="flex-1 text-xs text-text-secondary">
+                      Paused while you were away for {Math.max(1, Math.round(timer.idleAway / 60))} min
+                    </span>
+                    <button
+                      onClick={() => timer.resolveIdle(true)}
+                      className="no-drag px-2 py-1 text-[11px] rounded-lg text-text-primary cursor-pointer"
+                      style={{ background: 'rgba(255,255,255,0.08)' }}
+                    >
+                      Keep
+                    </button>
+                    <button
+                      onClick={() => timer.resolveIdle(false)}
+                      className="no-drag px-2 py-1 text-[11px] rounded-lg text-text-primary cursor-pointer"
+                      style={{ background: 'rgba(255,255,255,0.08)' }}
+                    >
+                      Discard
+                    </button>
+                  </div>
+                )}
               </div>
 
               {/* Focus Pal + Music */}
               <div className="px-4 pb-2 flex gap-2.5">
                 <button
                   data-tour="focus-pal"
-                  onClick={() => { setShowPalPicker(!showPalPicker); setShowSounds(false); setShowSettings(false); setShowTimerPicker(false); setShowAbout(false); setShowEyeBreak(false); }}
+                  onClick={() => { setShowPalPicker(!showPalPicker); setShowSounds(false); setShowSettings(false); setShowTimerPicker(false); setShowTasks(false); setShowAbout(false); setShowEyeBreak(false); }}
                   className="no-drag flex-1 px-4 py-2.5 flex items-center gap-2.5 hover:bg-bg-hover transition-colors cursor-pointer"
                   style={{ background: '#2c2c2e', borderRadius: 16 }}
                 >
@@ -453,7 +564,7 @@ export default function Home() {
 
                 <button
                   data-tour="music-btn"
-                  onClick={() => { setShowSounds(!showSounds); setShowPalPicker(false); setShowSettings(false); setShowTimerPicker(false); setShowAbout(false); setShowEyeBreak(false); }}
+                  onClick={() => { setShowSounds(!showSounds); setShowPalPicker(false); setShowSettings(false); setShowTimerPicker(false); setShowTasks(false); setShowAbout(false); setShowEyeBreak(false); }}
                   className="no-drag flex-1 px-4 py-2.5 flex items-center gap-2.5 hover:bg-bg-hover transition-colors cursor-pointer"
                   style={{ background: '#2c2c2e', borderRadius: 16 }}
                 >
@@ -477,7 +588,7 @@ export default function Home() {
               {/* Eye Break */}
               <div className="px-4 pb-3.5">
                 <button
-                  onClick={() => { setShowEyeBreak(!showEyeBreak); setShowSounds(false); setShowPalPicker(false); setShowSettings(false); setShowTimerPicker(false); setShowAbout(false); }}
+                  onClick={() => { setShowEyeBreak(!showEyeBreak); setShowSounds(false); setShowPalPicker(false); setShowSettings(false); setShowTimerPicker(false); setShowTasks(false); setShowAbout(false); }}
                   className="no-drag w-full px-4 py-2.5 flex items-center gap-2.5 hover:bg-bg-hover transition-colors cursor-pointer"
                   style={{ background: '#2c2c2e', borderRadius: 16 }}
                 >
@@ -533,6 +644,24 @@ export default function Home() {
                   </motion.div>
                 )}
 
+                {showTasks && !timer.isRunning && (
+                  <motion.div
+                    initial={{ height: 0, opacity: 0 }}
+                    animate={{ height: 'auto', opacity: 1 }}
+                    exit={{ height: 0, opacity: 0 }}
+                    className="overflow-hidden px-4"
+                  >
+                    <TaskPicker
+                      query={taskId ? '' : taskName}
+                      selectedId={taskId}
+                      onPick={(task) => {
+                        setTaskName(task.name);
+                        setTaskId(task.id);
+                        setShowTasks(false);
+                      }}
+                    />
+                  </motion.div>
+                )}
 
                 {showSettings && (
                   <motion.div
@@ -588,6 +717,85 @@ export default function Home() {
                           </div>
                         </div>
                       )}
+
+                      {/* Which display the pill sits on — only with several */}
+                      {monitors.length > 1 && (
+                        <div className="flex items-center justify-between">
+                          <span className="text-xs text-text-secondary">Display</span>
+                          <select
+                            value={typeof pillMonitor === 'string' ? pillMonitor : `named:${pillMonitor.named}`}
+                            onChange={(e) => choosePillMonitor(e.target.value)}
+                            className="no-drag px-2 py-1 text-[11px] text-text-primary rounded-lg cursor-pointer outline-none max-w-[160px]"
+                            style={{ background: '#1c1c1e' }}
+                          >
+                            <option value="primary">Primary</option>
+                            <option value="cursor">Follow cursor</option>
+                            {monitors.map(name => (
+                              <option key={name} value={`named:${name}`}>{name}</option>
+                            ))}
+                            {pillMonitor.named && !monitors.includes(pillMonitor.named) && (
+                              <option value={`named:${pillMonitor.named}`}>{pillMonitor.named} (disconnected)</option>
+                            )}
+                          </select>
+                        </div>
+                      )}
+
+                      {/* Do Not Disturb toggle */}
+                      <div className="flex items-center justify-between">
+                        <div className="flex flex-col">
+                          <span className="text-xs text-text-secondary">Do Not Disturb</span>
+                          <span className="text-[10px] text-text-muted">Silence other apps while focusing</span>
+                        </div>
+                        <button
+                          onClick={() => tauriBridge.updateSettings({ focusDnd: !focusDnd })}
+                          className={`no-drag w-9 h-5 rounded-full transition-all relative cursor-pointer ${
+                            focusDnd ? 'bg-success' : 'bg-border'
+                          }`}
+                        >
+                          <motion.div
+                            className="w-3.5 h-3.5 rounded-full bg-white absolute top-0.5"
+                            animate={{ left: focusDnd ? '18px' : '3px' }}
+                            transition={{ type: 'spring', stiffness: 500, damping: 30 }}
+                          />
+                        </button>
+                      </div>
+
+                      {/* Pause focus after this long without input */}
+                      {idleSettings && (
+                        <div className="flex items-center justify-between">
+                          <div className="flex flex-col">
+                            <span className="text-xs text-text-secondary">Pause when away</span>
+                            <span className="text-[10px] text-text-muted">No keyboard or mouse input while focusing</span>
+                          </div>
+                          <select
+                            value={idleSettings.enabled ? idleSettings.thresholdMinutes : 0}
+                            onChange={(e) => {
+                              const minutes = Number(e.target.value);
+                              tauriBridge.updateSettings({
+                                idle: minutes ? { enabled: true, thresholdMinutes: minutes } : { enabled: false },
+                              });
+                            }}
+                            className="no-drag px-2 py-1 text-[11px] text-text-primary rounded-lg cursor-pointer outline-none"
+                            style={{ background: '#1c1c1e' }}
+                          >
+                            <option value={0}>Off</option>
+                            {[2, 5, 10, 15, 30].map(m => (
+                              <option key={m} value={m}>After {m} min</option>
+                            ))}
+                            {![2, 5, 10, 15, 30].includes(idleSettings.thresholdMinutes) && (
+                              <option value={idleSettings.thresholdMinutes}>After {idleSettings.thresholdMinutes} min</option>
+                            )}
+                          </select>
+                        </div>
+                      )}
+
+                      <ShortcutSettings shortcuts={shortcuts} />
+
+                      <ApiSettings api={api} />
+
+                      <WebhookSettings webhooks={webhooks} />
+
+                      <HookSettings hooks={hooks} />
                     </div>
                   </motion.div>
                 )}
@@ -648,7 +856,7 @@ export default function Home() {
                   >
                     <div className="p-3 mb-3" style={{ background: '#2c2c2e', borderRadius: 16 }}>
                       <div className="flex gap-2 justify-center">
-                        {PALS.map((p, i) => (
+                        {pals.map((p, i) => (
                           <button
                             key={p.id}
                             onClick={() => { setSelectedPal(i); setShowPalPicker(false); }}
@@ -0,0 +1,71 @@
+'use client';
+import { useState } from 'react';
+import { motion } from 'framer-motion';
+import { tauriBridge } from '../lib/tauri-bridge';
+
+export default function ApiSettings({ api }) {
+  const [error, setError] = useState(null);
+  const [copied, setCopied] = useState(false);
+  if (!api) return null;
+
+  const update = async (patch) => {
+    try {
+      await tauriBridge.updateSettings({ api: patch });
+      setError(null);
+    } catch (e) {
+      setError(String(e));
+    }
+  };
+
+  const copyToken = async () => {
+    await navigator.clipboard.writeText(api.token);
+    setCopied(true);
+    setTimeout(() => setCopied(false), 1500);
+  };
+
+  return (
+    <div className="space-y-1.5 pt-1">
+      <div className="flex items-center justify-between">
+        <div className="flex flex-col">
+          <span className="text-xs text-text-secondary">Local API</span>
+          <span className="text-[10px] text-text-muted">http://127.0.0.1:{api.port}</span>
+        </div>
+        <button
+          onClick={() => update({ enabled: !api.enabled })}
+          className={`no-drag w-9 h-5 rounded-full transition-all relative cursor-pointer ${
+            api.enabled ? 'bg-success' : 'bg-border'
+          }`}
+        >
+          <motion.div
+            className="w-3.5 h-3.5 rounded-full bg-white absolute top-0.5"
+            animate={{ left: api.enabled ? '18px' : '3px' }}
+            transition={{ type: 'spring', stiffness: 500, damping: 30 }}
+          />
+        </button>
+      </div>
+      {api.enabled && api.token && (
+        <div className="flex items-center justify-between gap-2">
+          <span className="text-[11px] text-text-muted font-mono truncate">{api.token.slice(0, 8)}…</span>
+          <div className="flex gap-1">
+            <button
+              onClick={copyToken}
+              className="no-drag px-2 py-0.5 text-[11px] rounded-lg cursor-pointer text-text-secondary hover:text-text-primary"
+              style={{ background: '#1c1c1e' }}
+            >
+              {copied ? 'Copied' : 'Copy token'}
+            </button>
+            {/* An empty token makes Rust issue a new one */}
+            <button
+              onClick={() => update({ token: '' })}
+              className="no-drag px-2 py-0.5 text-[11px] rounded-lg cursor-pointer text-text-secondary hover:text-text-primary"
+              style={{ background: '#1c1c1e' }}
+            >
+              New
+            </button>
+          </div>
+        </div>
+      )}
+      {error && <div className="text-[10px] text-warning">{error}</div>}
+    </div>
+  );
+}
@@ -0,0 +1,70 @@
+'use client';
+import { useEffect, useState } from 'react';
+import { tauriBridge } from '../lib/tauri-bridge';
+import { LIFECYCLE_EVENTS as EVENTS } from '../lib/lifecycle-events';
+
+// Shell commands per session event, one per line
+export default function HookSettings({ hooks }) {
+  const [event, setEvent] = useState('focusStart');
+  const [draft, setDraft] = useState('');
+  const [error, setError] = useState(null);
+  const [runs, setRuns] = useState([]);
+
+  const saved = (hooks?.commands?.[event] || []).join('\n');
+  useEffect(() => setDraft(saved), [saved]);
+
+  useEffect(() => {
+    tauriBridge.listHookRuns(3).then(setRuns);
+    const unlisten = tauriBridge.onHookRun((run) => setRuns(prev => [run, ...prev].slice(0, 3)));
+    return () => { unlisten.then(fn => fn && fn()); };
+  }, []);
+
+  if (!hooks) return null;
+
+  const save = async () => {
+    if (draft === saved) return;
+    const commands = draft.split('\n').map(c => c.trim()).filter(Boolean);
+    try {
+      await tauriBridge.updateSettings({ hooks: { commands: { [event]: commands } } });
+      setError(null);
+    } catch (e) {
+      setError(String(e));
+    }
+  };
+
+  return (
+    <div className="space-y-1.5 pt-1">
+      <div className="flex items-center justify-between">
+        <span className="text-xs text-text-secondary">Shell hooks</span>
+        <select
+          value={event}
+          onChange={(e) => setEvent(e.target.value)}
+          className="no-drag px-2 py-1 text-[11px] text-text-primary rounded-lg cursor-pointer outline-none"
+          style={{ background: '#1c1c1e' }}
+        >
+          {EVENTS.map(({ id, label }) => (
+            <option key={id} value={id}>{label}</option>
+          ))}
+        </select>
+      </div>
+      <textarea
+        value={draft}
+        onChange={(e) => setDraft(e.target.value)}
+        onBlur={save}
+        placeholder={'One command per line, e.g.\npkill -x Slack'}
+        rows={2}
+        className="no-drag w-full px-2 py-1 text-[10px] font-mono text-text-primary rounded-lg outline-none resize-none"
+        style={{ background: '#1c1c1e' }}
+      />
+      {runs.map(run => (
+        <div key={run.id} className="flex items-center justify-between text-[10px] text-text-muted" title={run.output}>
+          <span className="truncate font-mono">{run.command}</span>
+          <span className={run.exitCode === 0 ? 'text-success' : 'text-warning'}>
+            {run.timedOut ? 'timed out' : run.exitCode === null ? 'failed' : `exit ${run.exitCode}`}
+          </span>
+        </div>
+      ))}
+      {error && <div className="text-[10px] text-warning">{error}</div>}
+    </div>
+  );
+}
@@ -0,0 +1,78 @@
+'use client';
+import { useState } from 'react';
+import { tauriBridge } from '../lib/tauri-bridge';
+
+const ACTIONS = [
+  { id: 'togglePopover', label: 'Show / hide' },
+  { id: 'startPause', label: 'Start / pause' },
+  { id: 'reset', label: 'Reset timer' },
+  { id: 'skipPhase', label: 'Skip phase' },
+  { id: 'eyeBreakNow', label: 'Eye break now' },
+  { id: 'muteSounds', label: 'Mute sounds' },
+  { id: 'openHistory', label: 'History' },
+];
+
+const MODIFIER_CODES = ['ShiftLeft', 'ShiftRight', 'ControlLeft', 'ControlRight', 'AltLeft', 'AltRight', 'MetaLeft', 'MetaRight'];
+
+// KeyboardEvent -> accelerator string Rust understands, e.g. "Super+Shift+KeyF"
+function toAccelerator(e) {
+  if (MODIFIER_CODES.includes(e.code)) return null;
+  const parts = [];
+  if (e.metaKey) parts.push('Super');
+  if (e.ctrlKey) parts.push('Ctrl');
+  if (e.altKey) parts.push('Alt');
+  if (e.shiftKey) parts.push('Shift');
+  // Bare keys would swallow normal typing everywhere; F-keys are fine alone
+  if (parts.length === 0 && !/^F\d+$/.test(e.code)) return null;
+  return [...parts, e.code].join('+');
+}
+
+function display(accelerator) {
+  return accelerator.replace(/Key([A-Z])/g, '$1').replace(/Digit(\d)/g, '$1').replace(/\+/g, ' + ');
+}
+
+export default function ShortcutSettings({ shortcuts = {} }) {
+  const [recording, setRecording] = useState(null);
+  const [error, setError] = useState(null);
+
+  const save = async (action, accelerator) => {
+    setRecording(null);
+    try {
+      await tauriBridge.setShortcut(action, accelerator);
+      setError(null);
+    } catch (e) {
+      setError(String(e));
+    }
+  };
+
+  const onKeyDown = (action) => (e) => {
+    e.preventDefault();
+    if (e.key === 'Escape') return setRecording(null);
+    if (e.key === 'Backspace' || e.key === 'Delete') return save(action, null);
+    const accelerator = toAccelerator(e);
+    if (accelerator) save(action, accelerator);
+  };
+
+  return (
+    <div className="space-y-1.5 pt-1">
+      <span className="text-xs text-text-secondary">Shortcuts</span>
+      {ACTIONS.map(({ id, label }) => (
+        <div key={id} className="flex items-center justify-between">
+          <span className="text-[11px] text-text-muted">{label}</span>
+          <button
+            onClick={() => setRecording(id)}
+            onKeyDown={recording === id ? onKeyDown(id) : undefined}
+            onBlur={() => recording === id && setRecording(null)}
+            className={`no-drag px-2 py-0.5 text-[11px] rounded-lg cursor-pointer min-w-[70px] ${
+              recording === id ? 'ring-1 ring-accent/50 text-text-primary' : 'text-text-secondary hover:text-text-primary'
+            }`}
+            style={{ background: '#1c1c1e' }}
+          >
+            {recording === id ? 'Press keys…' : shortcuts[id] ? display(shortcuts[id]) : '—'}
+          </button>
+        </div>
+      ))}
+      {error && <div className="text-[10px] text-warning">{error}</div>}
+    </div>
+  );
+}
@@ -0,0 +1,119 @@
+'use client';
+import { useState } from 'react';
+import { tauriBridge } from '../lib/tauri-bridge';
+import { useTasks, TaskProgress } from './TaskPicker';
+
+function TaskRow({ task, onError }) {
+  const [name, setName] = useState(null);
+
+  const run = (action) => action.then(() => onError(null), e => onError(String(e)));
+
+  const saveName = () => {
+    if (name !== null && name.trim() && name.trim() !== task.name) {
+      run(tauriBridge.renameTask(task.id, name.trim()));
+    }
+    setName(null);
+  };
+
+  const stepEstimate = (by) => {
+    const next = (task.estimate || 0) + by;
+    run(tauriBridge.setTaskEstimate(task.id, next > 0 ? next : null));
+  };
+
+  const hours = Math.floor(task.focusMinutes / 60);
+  const focus = hours ? `${hours}h ${task.focusMinutes % 60}m` : `${task.focusMinutes} min`;
+
+  return (
+    <div
+      className={`flex items-center gap-3 px-3.5 py-2.5 ${task.archived ? 'opacity-50' : ''}`}
+      style={{ background: '#2c2c2e', borderRadius: 12 }}
+    >
+      <div className="flex-1 min-w-0">
+        {name === null ? (
+          <button
+            onClick={() => setName(task.name)}
+            className="block w-full text-left text-[13px] font-medium text-text-primary truncate"
+            title="Rename"
+          >
+            {task.name}
+          </button>
+        ) : (
+          <input
+            autoFocus
+            value={name}
+            onChange={(e) => setName(e.target.value)}
+            onBlur={saveName}
+            onKeyDown={(e) => {
+              if (e.key === 'Enter') saveName();
+              if (e.key === 'Escape') setName(null);
+            }}
+            className="w-full bg-transparent text-[13px] font-medium text-text-primary outline-none border-b border-white/20"
+          />
+        )}
+        <div className="text-[11px] text-text-muted">{focus} focused</div>
+      </div>
+      <div className="flex items-center gap-1 shrink-0">
+        <button
+          onClick={() => stepEstimate(-1)}
+          disabled={!task.estimate}
+          className="w-5 h-5 rounded-md text-[11px] text-text-secondary disabled:opacity-30"
+          style={{ background: '#3a3a3c' }}
+        >
+          −
+        </button>
+        <TaskProgress task={task} />
+        <button
+          onClick={() => stepEstimate(1)}
+          className="w-5 h-5 rounded-md text-[11px] text-text-secondary"
+          style={{ background: '#3a3a3c' }}
+        >
+          +
+        </button>
+      </div>
+      <button
+        onClick={() => run(tauriBridge.archiveTask(task.id, !task.archived))}
+        className="text-[10px] px-2 py-1 rounded-md text-text-secondary shrink-0"
+        style={{ background: '#3a3a3c' }}
+      >
+        {task.archived ? 'Restore' : 'Archive'}
+      </button>
+    </div>
+  );
+}
+
+// Every task with its pomodoros against the estimate
+export default function TaskList() {
+  const [showArchived, setShowArchived] = useState(false);
+  const [error, setError] = useState(null);
+  const tasks = useTasks(showArchived);
+
+  return (
+    <div className="flex flex-col gap-1.5">
+      <div className="flex items-center justify-between mb-1">
+        <span className="text-[11px] text-text-muted">
+          {'\u{1F345}'} done / estimated
+        </span>
+        <label className="flex items-center gap-1.5 text-[11px] text-text-muted cursor-pointer">
+          <input
+            type="checkbox"
+            checked={showArchived}
+            onChange={(e) => setShowArchived(e.target.checked)}
+          />
+          Show archived
+        </label>
+      </div>
+      {error && <p className="text-[11px] text-warning">{error}</p>}
+      {tasks.length === 0 ? (
+        <p className="text-text-muted text-xs text-center mt-8">
+          No tasks yet. Add one from the task field in the popover.
+        </p>
+      ) : (
+        <div className="flex flex-col gap-1.5 overflow-y-auto" style={{ maxHeight: 'calc(100vh - 120px)' }}>
+          {tasks.map(task => (
+            <TaskRow key={task.id} task={task} onError={setError} />
+          ))}
+        </div>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,84 @@
+'use client';
+import { useEffect, useState } from 'react';
+import { tauriBridge } from '../lib/tauri-bridge';
+
+const ESTIMATES = [1, 2, 3, 4, 6, 8];
+
+export function useTasks(includeArchived = false) {
+  const [tasks, setTasks] = useState([]);
+  useEffect(() => {
+    const load = () => tauriBridge.listTasks(includeArchived).then(setTasks);
+    load();
+    const unlisten = tauriBridge.onTasksChanged(load);
+    return () => { unlisten.then(fn => fn && fn()); };
+  }, [includeArchived]);
+  return tasks;
+}
+
+export function TaskProgress({ task }) {
+  const over = task.estimate && task.pomodoros > task.estimate;
+  return (
+    <span className={`text-[10px] tabular-nums ${over ? 'text-warning' : 'text-text-muted'}`}>
+      {task.pomodoros}{task.estimate ? `/${task.estimate}` : ''} {'\u{1F345}'}
+    </span>
+  );
+}
+
+// Pick the task the next session counts towards, or add one
+export default function TaskPicker({ query = '', selectedId, onPick }) {
+  const tasks = useTasks();
+  const [estimate, setEstimate] = useState('');
+  const [error, setError] = useState(null);
+
+  const name = query.trim();
+  const shown = tasks.filter(t => t.name.toLowerCase().includes(name.toLowerCase()));
+  const exists = tasks.some(t => t.name.toLowerCase() === name.toLowerCase());
+
+  const add = async () => {
+    try {
+      const task = await tauriBridge.createTask(name, estimate ? Number(estimate) : null);
+      setError(null);
+      setEstimate('');
+      if (task) onPick(task);
+    } catch (e) {
+      setError(String(e));
+    }
+  };
+
+  return (
+    <div className="p-2 mb-3 space-y-0.5" style={{ background: '#2c2c2e', borderRadius: 16 }}>
+      {shown.map(task => (
+        <button
+          key={task.id}
+          onClick={() => onPick(task)}
+          className={`no-drag w-full flex items-center justify-between gap-2 px-2 py-1.5 rounded-lg text-left cursor-pointer ${
+            task.id === selectedId ? 'bg-white/15' : 'hover:bg-white/10'
+          }`}
+        >
+          <span className="text-xs text-text-primary truncate">{task.name}</span>
+          <TaskProgress task={task} />
+        </button>
+      ))}
+      {name && !exists && (
+        <div className="flex items-center gap-2 px-2 py-1">
+          <button onClick={add} className="no-drag flex-1 text-left text-xs text-text-secondary hover:text-text-primary truncate cursor-pointer">
+            Add “{name}”
+          </button>
+          <select
+            value={estimate}
+            onChange={(e) => setEstimate(e.target.value)}
+            className="no-drag px-1.5 py-0.5 text-[10px] text-text-primary rounded-md cursor-pointer outline-none"
+            style={{ background: '#1c1c1e' }}
+          >
+            <option value="">No estimate</option>
+            {ESTIMATES.map(n => <option key={n} value={n}>{n} {'\u{1F345}'}</option>)}
+          </select>
+        </div>
+      )}
+      {!shown.length && !name && (
+        <div className="px-2 py-1.5 text-[11px] text-text-muted">Type a task name to add it</div>
+      )}
+      {error && <div className="px-2 text-[10px] text-warning">{error}</div>}
+    </div>
+  );
+}
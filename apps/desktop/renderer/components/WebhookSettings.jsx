@@ -0,0 +1,123 @@
+'use client';
+import { useEffect, useState } from 'react';
+import { tauriBridge } from '../lib/tauri-bridge';
+import { LIFECYCLE_EVENTS as EVENTS } from '../lib/lifecycle-events';
+
+const inputStyle = { background: '#1c1c1e' };
+
+function WebhookRow({ hook, onChange, onRemove }) {
+  const [editingTemplate, setEditingTemplate] = useState(false);
+  const [template, setTemplate] = useState(hook.template);
+  useEffect(() => setTemplate(hook.template), [hook.template]);
+
+  // An empty list means every event
+  const selected = hook.events.length ? hook.events : EVENTS.map(e => e.id);
+  const toggleEvent = (id) => {
+    const events = selected.includes(id) ? selected.filter(e => e !== id) : [...selected, id];
+    if (events.length === 0) return;
+    onChange({ events: events.length === EVENTS.length ? [] : events });
+  };
+
+  return (
+    <div className="space-y-1 p-2 rounded-xl" style={inputStyle}>
+      <div className="flex items-center justify-between gap-2">
+        <span className={`text-[11px] truncate ${hook.enabled ? 'text-text-primary' : 'text-text-muted line-through'}`}>{hook.url}</span>
+        <div className="flex gap-2 shrink-0">
+          <button onClick={() => onChange({ enabled: !hook.enabled })} className="no-drag text-[10px] text-text-muted hover:text-text-primary cursor-pointer">
+            {hook.enabled ? 'Pause' : 'Enable'}
+          </button>
+          <button onClick={onRemove} className="no-drag text-[10px] text-text-muted hover:text-warning cursor-pointer">Remove</button>
+        </div>
+      </div>
+      <div className="flex flex-wrap gap-1">
+        {EVENTS.map(({ id, label }) => (
+          <button
+            key={id}
+            onClick={() => toggleEvent(id)}
+            className={`no-drag px-1.5 py-0.5 text-[10px] rounded-md cursor-pointer ${
+              selected.includes(id) ? 'bg-white/15 text-white' : 'text-text-muted'
+            }`}
+          >
+            {label}
+          </button>
+        ))}
+        <button
+          onClick={() => setEditingTemplate(v => !v)}
+          className="no-drag px-1.5 py-0.5 text-[10px] rounded-md cursor-pointer text-text-muted hover:text-text-secondary"
+        >
+          Payload…
+        </button>
+      </div>
+      {editingTemplate && (
+        <textarea
+          value={template}
+          onChange={(e) => setTemplate(e.target.value)}
+          onBlur={() => template !== hook.template && onChange({ template })}
+          placeholder='Default payload. Or JSON like {"text": "{{event}}, {{minutes}} min"}'
+          rows={3}
+          className="no-drag w-full px-2 py-1 text-[10px] font-mono text-text-primary rounded-lg outline-none resize-none bg-black/30"
+        />
+      )}
+    </div>
+  );
+}
+
+export default function WebhookSettings({ webhooks = [] }) {
+  const [newUrl, setNewUrl] = useState('');
+  const [error, setError] = useState(null);
+  const [deliveries, setDeliveries] = useState([]);
+
+  useEffect(() => {
+    tauriBridge.listWebhookDeliveries(3).then(setDeliveries);
+    const unlisten = tauriBridge.onWebhookDelivery((d) => setDeliveries(prev => [d, ...prev].slice(0, 3)));
+    return () => { unlisten.then(fn => fn && fn()); };
+  }, []);
+
+  // Arrays replace wholesale in a settings patch, so always send the full list
+  const save = async (next) => {
+    try {
+      await tauriBridge.updateSettings({ webhooks: next });
+      setError(null);
+      return true;
+    } catch (e) {
+      setError(String(e));
+      return false;
+    }
+  };
+
+  const add = async () => {
+    if (!newUrl.trim()) return;
+    if (await save([...webhooks, { url: newUrl.trim() }])) setNewUrl('');
+  };
+
+  return (
+    <div className="space-y-1.5 pt-1">
+      <span className="text-xs text-text-secondary">Webhooks</span>
+      {webhooks.map((hook, i) => (
+        <WebhookRow
+          key={i}
+          hook={hook}
+          onChange={(patch) => save(webhooks.map((h, j) => (j === i ? { ...h, ...patch } : h)))}
+          onRemove={() => save(webhooks.filter((_, j) => j !== i))}
+        />
+      ))}
+      <input
+        value={newUrl}
+        onChange={(e) => setNewUrl(e.target.value)}
+        onKeyDown={(e) => e.key === 'Enter' && add()}
+        placeholder="https://… then Enter"
+        className="no-drag w-full px-2 py-1 text-[11px] text-text-primary rounded-lg outline-none"
+        style={inputStyle}
+      />
+      {deliveries.map(d => (
+        <div key={d.id} className="flex items-center justify-between text-[10px] text-text-muted">
+          <span className="truncate">{d.event} → {d.url}</span>
+          <span className={d.error ? 'text-warning' : 'text-success'}>
+            {d.error ? `failed (${d.attempts}×)` : d.status}
+          </span>
+        </div>
+      ))}
+      {error && <div className="text-[10px] text-warning">{error}</div>}
+    </div>
+  );
+}
@@ -1,7 +1,6 @@
 'use client';
 import { useState, useRef, useCallback, useEffect } from 'react';
-
-const STORAGE_KEY = 'meow-eyebreak';
+import { tauriBridge } from '../lib/tauri-bridge';
 
 const DEFAULTS = {
   enabled: true,
@@ -28,26 +27,19 @@ export function useEyeBreak({ onBreakDue, onBreakEnd } = {}) {
   useEffect(() => { onBreakDueRef.current = onBreakDue; }, [onBreakDue]);
   useEffect(() => { onBreakEndRef.current = onBreakEnd; }, [onBreakEnd]);
 
-  // Load settings from localStorage
+  // Load settings from the shared store and follow changes from other windows
   useEffect(() => {
-    try {
-      const saved = localStorage.getItem(STORAGE_KEY);
-      if (saved) {
-        const parsed = JSON.parse(saved);
-        setSettings(s => ({ ...s, ...parsed }));
-      }
-    } catch {}
+    const apply = (s) => {
+      if (s?.eyeBreak) setSettings(prev => ({ ...prev, ...s.eyeBreak }));
+    };
+    tauriBridge.getSettings().then(apply);
+    const unlisten = tauriBridge.onSettingsChanged(apply);
+    return () => { unlisten.then(fn => fn && fn()); };
   }, []);
 
-  // Persist settings
-  useEffect(() => {
-    try {
-      localStorage.setItem(STORAGE_KEY, JSON.stringify(settings));
-    } catch {}
-  }, [settings]);
-
   const updateSettings = useCallback((updates) => {
     setSettings(s => ({ ...s, ...updates }));
+    tauriBridge.updateSettings({ eyeBreak: updates });
   }, []);
 
   // Start an eye break
@@ -77,6 +69,20 @@ export function useEyeBreak({ onBreakDue, onBreakEnd } = {}) {
     setTimeSinceLastBreak(0);
   }, [dismissBreak]);
 
+  // Away from the screen for at least a break's worth counts as one. A
+  // shorter suspend froze the interval below, so add the time it missed.
+  useEffect(() => {
+    const unlisten = tauriBridge.onSystemResume(({ reason, awaySeconds, eyeBreakReset }) => {
+      if (eyeBreakReset) {
+        if (breakTimerRef.current) dismissBreak();
+        setTimeSinceLastBreak(0);
+      } else if (reason === 'suspend') {
+        setTimeSinceLastBreak(prev => prev + awaySeconds);
+      }
+    });
+    return () => { unlisten.then(fn => fn && fn()); };
+  }, [dismissBreak]);
+
   // Break countdown timer
   useEffect(() => {
     if (!isBreakActive) return;
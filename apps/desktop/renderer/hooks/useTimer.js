@@ -1,9 +1,13 @@
 'use client';
-import { useState, useRef, useCallback, useEffect } from 'react';
+import { useState, useRef, useCallback, useEffect, useMemo } from 'react';
+import { tauriBridge } from '../lib/tauri-bridge';
 
+// The countdown itself lives in Rust (src-tauri/src/timer.rs) so it keeps
+// time while the webview is throttled. This hook mirrors its state from
+// "timer-tick" events and forwards user actions as commands.
 export function useTimer(settings = {}) {
   const {
-    minutes: initialMinutes = 25,
+    minutes = 25,
     pomodoroMode = false,
     workMinutes = 25,
     shortBreakMinutes = 5,
@@ -12,24 +16,78 @@ export function useTimer(settings = {}) {
     onComplete,
   } = settings;
 
-  const [mode, setMode] = useState('work'); // work | shortBreak | longBreak
-  const [minutes, setMinutes] = useState(initialMinutes);
-  const [timeLeft, setTimeLeft] = useState(initialMinutes * 60);
-  const [isRunning, setIsRunning] = useState(false);
-  const [completedSessions, setCompletedSessions] = useState(0);
-  const intervalRef = useRef(null);
-
-  const getCurrentDuration = useCallback(() => {
-    if (!pomodoroMode) return minutes * 60;
-    switch (mode) {
-      case 'work': return workMinutes * 60;
-      case 'shortBreak': return shortBreakMinutes * 60;
-      case 'longBreak': return longBreakMinutes * 60;
-      default: return workMinutes * 60;
-    }
-  }, [pomodoroMode, minutes, mode, workMinutes, shortBreakMinutes, longBreakMinutes]);
+  const config = useMemo(() => ({
+    minutes,
+    pomodoroMode,
+    workMinutes,
+    shortBreakMinutes,
+    longBreakMinutes,
+    longBreakInterval,
+  }), [minutes, pomodoroMode, workMinutes, shortBreakMinutes, longBreakMinutes, longBreakInterval]);
+
+  const [state, setState] = useState({
+    phase: 'work',
+    isRunning: false,
+    timeLeft: minutes * 60,
+    totalTime: minutes * 60,
+    completedSessions: 0,
+    idlePaused: false,
+  });
+  // Seconds away when the user came back from an idle pause, until they
+  // decide whether to keep them.
+  const [idleAway, setIdleAway] = useState(null);
+
+  // Keep the latest config/callback in refs so the event listeners below
+  // are registered once rather than on every render.
+  const configRef = useRef(config);
+  const onCompleteRef = useRef(onComplete);
+  useEffect(() => { configRef.current = config; }, [config]);
+  useEffect(() => { onCompleteRef.current = onComplete; }, [onComplete]);
+
+  // Subscribe to the Rust timer
+  useEffect(() => {
+    if (typeof window === 'undefined') return;
+    const unlisteners = [];
+
+    const setup = async () => {
+      const current = await tauriBridge.getTimerState();
+      if (current) {
+        // A fresh, untouched timer picks up the persisted durations;
+        // a session already in progress (e.g. after a reload) is left alone.
+        if (!current.isRunning && current.timeLeft === current.totalTime) {
+          const fresh = await tauriBridge.resetTimer(configRef.current);
+          setState(fresh || current);
+        } else {
+          setState(current);
+        }
+      }
+      unlisteners.push(await tauriBridge.onTimerTick(setState));
+      unlisteners.push(await tauriBridge.onTimerPhaseChange((change) => {
+        if (!change.completed) return;
+        if (change.pomodoroMode) {
+          onCompleteRef.current?.(change.from, change.completedSessions, change.idleSeconds, change.minutes);
+        } else {
+          onCompleteRef.current?.(change.completedSessions, change.idleSeconds, change.minutes);
+        }
+      }));
+      unlisteners.push(await tauriBridge.onIdleReturn(({ idleSeconds }) => setIdleAway(idleSeconds)));
+      // Ticks sent while the webview was suspended may never have arrived
+      unlisteners.push(await tauriBridge.onSystemResume(async () => {
+        const latest = await tauriBridge.getTimerState();
+        if (latest) setState(latest);
+      }));
+    };
+    setup();
+
+    return () => { unlisteners.forEach((fn) => fn && fn()); };
+  }, []);
+
+  const { phase: mode, isRunning, timeLeft, totalTime, completedSessions, idlePaused } = state;
 
-  const totalTime = getCurrentDuration();
+  // Resumed or reset some other way; nothing left to decide
+  useEffect(() => {
+    if (!idlePaused) setIdleAway(null);
+  }, [idlePaused]);
   const progress = totalTime > 0 ? (totalTime - timeLeft) / totalTime : 0;
 
   const mins = Math.floor(timeLeft / 60);
@@ -40,57 +98,13 @@ export function useTimer(settings = {}) {
     ? (mode === 'work' ? 'Focus' : mode === 'shortBreak' ? 'Short Break' : 'Long Break')
     : '';
 
-  const stop = useCallback(() => {
-    if (intervalRef.current) {
-      clearInterval(intervalRef.current);
-      intervalRef.current = null;
-    }
-    setIsRunning(false);
-  }, []);
-
-  const setDuration = useCallback((newMinutes) => {
-    setMinutes(newMinutes);
-    if (!isRunning) {
-      setTimeLeft(newMinutes * 60);
-    }
-  }, [isRunning]);
-
-  const switchMode = useCallback((newMode) => {
-    stop();
-    setMode(newMode);
-    const dur = newMode === 'work' ? workMinutes : newMode === 'shortBreak' ? shortBreakMinutes : longBreakMinutes;
-    setTimeLeft(dur * 60);
-  }, [stop, workMinutes, shortBreakMinutes, longBreakMinutes]);
-
-  const handleComplete = useCallback(() => {
-    stop();
-    const newCompleted = completedSessions + 1;
-    setCompletedSessions(newCompleted);
-
-    if (pomodoroMode) {
-      if (mode === 'work') {
-        const isLongBreak = newCompleted % longBreakInterval === 0;
-        const nextMode = isLongBreak ? 'longBreak' : 'shortBreak';
-        onComplete?.('work', newCompleted);
-        switchMode(nextMode);
-      } else {
-        onComplete?.(mode, completedSessions);
-        switchMode('work');
-      }
-    } else {
-      setTimeLeft(minutes * 60);
-      onComplete?.(newCompleted);
-    }
-  }, [completedSessions, minutes, mode, pomodoroMode, longBreakInterval, stop, switchMode, onComplete]);
-
   const start = useCallback(() => {
-    if (timeLeft <= 0) return;
-    setIsRunning(true);
-  }, [timeLeft]);
+    tauriBridge.startTimer(configRef.current);
+  }, []);
 
   const pause = useCallback(() => {
-    stop();
-  }, [stop]);
+    tauriBridge.pauseTimer();
+  }, []);
 
   const toggle = useCallback(() => {
     if (isRunning) pause();
@@ -98,58 +112,31 @@ export function useTimer(settings = {}) {
   }, [isRunning, pause, start]);
 
   const reset = useCallback(() => {
-    stop();
-    if (pomodoroMode) {
-      const dur = mode === 'work' ? workMinutes : mode === 'shortBreak' ? shortBreakMinutes : longBreakMinutes;
-      setTimeLeft(dur * 60);
-    } else {
-      setTimeLeft(minutes * 60);
-    }
-  }, [stop, pomodoroMode, mode, minutes, workMinutes, shortBreakMinutes, longBreakMinutes]);
+    tauriBridge.resetTimer(configRef.current);
+  }, []);
 
-  // Update timeLeft when duration settings change (only when not running)
-  // Use a ref to track if we're just pausing vs actually changing settings
-  const prevSettingsRef = useRef({ minutes, workMinutes, shortBreakMinutes, longBreakMinutes, pomodoroMode });
-  useEffect(() => {
-    const prev = prevSettingsRef.current;
-    const settingsChanged =
-      prev.minutes !== minutes ||
-      prev.workMinutes !== workMinutes ||
-      prev.shortBreakMinutes !== shortBreakMinutes ||
-      prev.longBreakMinutes !== longBreakMinutes ||
-      prev.pomodoroMode !== pomodoroMode;
-    prevSettingsRef.current = { minutes, workMinutes, shortBreakMinutes, longBreakMinutes, pomodoroMode };
-
-    if (!isRunning && settingsChanged) {
-      if (pomodoroMode) {
-        const dur = mode === 'work' ? workMinutes : mode === 'shortBreak' ? shortBreakMinutes : longBreakMinutes;
-        setTimeLeft(dur * 60);
-      } else {
-        setTimeLeft(minutes * 60);
-      }
-    }
-  }, [minutes, workMinutes, shortBreakMinutes, longBreakMinutes, pomodoroMode]);
+  const resolveIdle = useCallback((keep) => {
+    tauriBridge.resolveIdle(keep);
+  }, []);
 
-  // Timer tick
-  useEffect(() => {
-    if (!isRunning) return;
+  const skip = useCallback(() => {
+    tauriBridge.skipPhase();
+  }, []);
 
-    intervalRef.current = setInterval(() => {
-      setTimeLeft((prev) => {
-        if (prev <= 1) {
-          handleComplete();
-          return 0;
-        }
-        return prev - 1;
-      });
-    }, 1000);
+  const setDuration = useCallback((newMinutes) => {
+    if (!isRunning) {
+      tauriBridge.resetTimer({ ...configRef.current, minutes: newMinutes });
+    }
+  }, [isRunning]);
 
-    return () => {
-      if (intervalRef.current) {
-        clearInterval(intervalRef.current);
-      }
-    };
-  }, [isRunning, handleComplete]);
+  // Push duration changes to Rust (only when not running, and not on mount —
+  // the subscribe effect above handles the initial sync)
+  const prevConfigRef = useRef(config);
+  useEffect(() => {
+    if (prevConfigRef.current === config) return;
+    prevConfigRef.current = config;
+    if (!isRunning) tauriBridge.resetTimer(config);
+  }, [config]);
 
   return {
     mode,
@@ -161,11 +148,13 @@ export function useTimer(settings = {}) {
     progress,
     completedSessions,
     totalTime,
+    idleAway,
     start,
     pause,
     toggle,
     reset,
+    skip,
+    resolveIdle,
     setDuration,
-    switchMode,
   };
 }
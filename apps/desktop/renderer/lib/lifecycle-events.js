@@ -0,0 +1,9 @@
+// Session events webhooks and shell hooks can fire on (Rust `LifecycleEvent`)
+export const LIFECYCLE_EVENTS = [
+  { id: 'focusStart', label: 'Focus start' },
+  { id: 'focusComplete', label: 'Focus done' },
+  { id: 'focusStop', label: 'Focus stopped' },
+  { id: 'breakStart', label: 'Break' },
+  { id: 'eyeBreakStart', label: 'Eye break' },
+  { id: 'eyeBreakEnd', label: 'Eye break end' },
+];
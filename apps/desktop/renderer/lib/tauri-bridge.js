@@ -9,6 +9,59 @@ function getTauri() {
   return null;
 }
 
+// localStorage keys used before settings moved to Rust. Read once, pushed
+// into the settings store, then removed.
+// A whole number clamped to [min, max], or undefined if `value` isn't one
+function clampInt(value, min, max) {
+  const n = typeof value === 'number' ? value : parseInt(value, 10);
+  return Number.isFinite(n) ? Math.min(max, Math.max(min, Math.round(n))) : undefined;
+}
+
+function readJson(key) {
+  try { return JSON.parse(localStorage.getItem(key) || 'null') || {}; } catch { return {}; }
+}
+
+// Copy the fields that are set, so an unreadable one is left out rather
+// than making update_settings reject the whole patch
+function defined(fields) {
+  return Object.fromEntries(Object.entries(fields).filter(([, v]) => v !== undefined));
+}
+
+// Each value is clamped to the range Rust accepts, or dropped if it can't be read
+function takeLegacySettings(palCount) {
+  const patch = {};
+  try {
+    const pomodoro = localStorage.getItem('meow-pomodoro');
+    const pomodoroSettings = readJson('meow-pomodoro-settings');
+    const timer = defined({
+      minutes: clampInt(localStorage.getItem('meow-minutes'), 1, 240),
+      pomodoroMode: pomodoro ? pomodoro === 'true' : undefined,
+      workMinutes: clampInt(pomodoroSettings.workMinutes, 1, 240),
+      shortBreakMinutes: clampInt(pomodoroSettings.shortBreakMinutes, 1, 240),
+      longBreakMinutes: clampInt(pomodoroSettings.longBreakMinutes, 1, 240),
+      longBreakInterval: clampInt(pomodoroSettings.longBreakInterval, 1, 12),
+    });
+    if (Object.keys(timer).length) patch.timer = timer;
+    const pal = clampInt(localStorage.getItem('meow-pal'), 0, palCount - 1);
+    if (pal !== undefined && palCount > 0) patch.pal = pal;
+    const legacyEyeBreak = readJson('meow-eyebreak');
+    const eyeBreak = defined({
+      enabled: typeof legacyEyeBreak.enabled === 'boolean' ? legacyEyeBreak.enabled : undefined,
+      intervalMinutes: clampInt(legacyEyeBreak.intervalMinutes, 1, 240),
+      breakDurationSeconds: clampInt(legacyEyeBreak.breakDurationSeconds, 5, 600),
+      strictMode: typeof legacyEyeBreak.strictMode === 'boolean' ? legacyEyeBreak.strictMode : undefined,
+    });
+    if (Object.keys(eyeBreak).length) patch.eyeBreak = eyeBreak;
+  } catch {}
+  return Object.keys(patch).length ? patch : null;
+}
+
+function hasLegacySettings() {
+  try { return LEGACY_SETTINGS_KEYS.some((key) => localStorage.getItem(key) !== null); } catch { return false; }
+}
+
+const LEGACY_SETTINGS_KEYS = ['meow-minutes', 'meow-pal', 'meow-pomodoro', 'meow-pomodoro-settings', 'meow-eyebreak'];
+
 export const tauriBridge = {
   resizeWindow: (height) => {
     const t = getTauri();
@@ -45,21 +98,87 @@ export const tauriBridge = {
     if (t) t.core.invoke('focus_window');
   },
 
-  onStartFocus: async (cb) => {
+  // Hover fallback for platforms where Rust can't read the cursor (Wayland)
+  setPointerInside: (inside) => {
+    const t = getTauri();
+    if (t) t.core.invoke('set_pointer_inside', { inside });
+  },
+
+  // { wakeupsPerMinute, pollIntervalMs } of the hover tracker
+  getTrackerStats: async () => {
+    const t = getTauri();
+    if (t) return t.core.invoke('get_tracker_stats');
+    return null;
+  },
+
+  // ── Timer (runs in Rust; the UI renders "timer-tick" snapshots) ──
+
+  getTimerState: async () => {
     const t = getTauri();
-    if (t) return t.event.listen('tray-start-focus', () => cb());
+    if (t) return t.core.invoke('get_timer_state');
+    return null;
+  },
+
+  startTimer: async (config) => {
+    const t = getTauri();
+    if (t) return t.core.invoke('start_timer', { config });
+    return null;
+  },
+
+  pauseTimer: async () => {
+    const t = getTauri();
+    if (t) return t.core.invoke('pause_timer');
+    return null;
+  },
+
+  // Resume after an idle pause, counting the time away as focus or not
+  resolveIdle: async (keep) => {
+    const t = getTauri();
+    if (t) return t.core.invoke('resolve_idle', { keep });
+    return null;
+  },
+
+  resetTimer: async (config) => {
+    const t = getTauri();
+    if (t) return t.core.invoke('reset_timer', { config });
+    return null;
+  },
+
+  skipPhase: async () => {
+    const t = getTauri();
+    if (t) return t.core.invoke('skip_phase');
+    return null;
+  },
+
+  onTimerTick: async (cb) => {
+    const t = getTauri();
+    if (t) return t.event.listen('timer-tick', (event) => cb(event.payload));
     return () => {};
   },
 
-  onPause: async (cb) => {
+  onTimerPhaseChange: async (cb) => {
     const t = getTauri();
-    if (t) return t.event.listen('tray-pause', () => cb());
+    if (t) return t.event.listen('timer-phase-change', (event) => cb(event.payload));
     return () => {};
   },
 
-  onReset: async (cb) => {
+  onIdleReturn: async (cb) => {
     const t = getTauri();
-    if (t) return t.event.listen('tray-reset', () => cb());
+    if (t) return t.event.listen('idle-return', (event) => cb(event.payload));
+    return () => {};
+  },
+
+  // { reason: 'suspend' | 'lock' }
+  onSystemSleep: async (cb) => {
+    const t = getTauri();
+    if (t) return t.event.listen('system-sleep', (event) => cb(event.payload));
+    return () => {};
+  },
+
+  // { reason, awaySeconds, eyeBreakReset }
+  onSystemResume: async (cb) => {
+    const t = getTauri();
+    if (t) return t.event.listen('system-resume', (event) => cb(event.payload));
     return () => {};
   },
 
@@ -88,23 +207,96 @@ export const tauriBridge = {
     }
   },
 
+  // The whole history, newest first. Use listSessions to page through it.
   getHistory: async () => {
     const t = getTauri();
     if (t) {
-      const data = await t.core.invoke('get_history');
-      return JSON.parse(data);
+      const sessions = [];
+      for (;;) {
+        const page = await t.core.invoke('list_sessions', { offset: sessions.length, limit: 500 });
+        sessions.push(...page.sessions);
+        if (!page.sessions.length || sessions.length >= page.total) return sessions;
+      }
     }
     // Fallback for browser dev
     try { return JSON.parse(localStorage.getItem('meow-history') || '[]'); } catch { return []; }
   },
 
+  // Returns { sessions, total }, newest first. `filters` may set
+  // type, focusOnly, task, pal, completed, from and to.
+  listSessions: async (offset = 0, limit = 50, filters = {}) => {
+    const t = getTauri();
+    if (t) return t.core.invoke('list_sessions', { offset, limit, filters });
+    return { sessions: [], total: 0 };
+  },
+
+  // Aggregated focus time. `range` is { from, to } (ISO strings, either
+  // optional); `groupBy` is 'day' | 'week' | 'month' | 'task' | 'pal'.
+  getStats: async (range = {}, groupBy = 'day') => {
+    const t = getTauri();
+    if (t) return t.core.invoke('get_stats', { range, groupBy });
+    return null;
+  },
+
+  // Export sessions as 'csv' | 'json' | 'ics'. Opens a native save dialog;
+  // resolves to the written path, or null if cancelled.
+  exportHistory: async (format, range = {}) => {
+    const t = getTauri();
+    if (t) return t.core.invoke('export_history', { format, range });
+    return null;
+  },
+
+  // Import another timer's CSV/JSON export via a native open dialog.
+  // Resolves to { imported, skipped, malformed }, or null if cancelled.
+  importHistory: async () => {
+    const t = getTauri();
+    if (t) return t.core.invoke('import_history', {});
+    return null;
+  },
+
+  // ── Tasks (each with `pomodoros` done against its `estimate`) ──
+
+  listTasks: async (includeArchived = false) => {
+    const t = getTauri();
+    if (t) return t.core.invoke('list_tasks', { includeArchived });
+    return [];
+  },
+
+  createTask: async (name, estimate = null) => {
+    const t = getTauri();
+    if (t) return t.core.invoke('create_task', { name, estimate });
+    return null;
+  },
+
+  renameTask: async (id, name) => {
+    const t = getTauri();
+    if (t) return t.core.invoke('rename_task', { id, name });
+    return null;
+  },
+
+  // `estimate` in pomodoros, or null to clear it
+  setTaskEstimate: async (id, estimate) => {
+    const t = getTauri();
+    if (t) return t.core.invoke('set_task_estimate', { id, estimate });
+    return null;
+  },
+
+  archiveTask: async (id, archived = true) => {
+    const t = getTauri();
+    if (t) return t.core.invoke('archive_task', { id, archived });
+    return null;
+  },
+
+  onTasksChanged: async (cb) => {
+    const t = getTauri();
+    if (t) return t.event.listen('tasks-changed', () => cb());
+    return () => {};
+  },
+
   saveHistoryEntry: async (entry) => {
     const t = getTauri();
     if (t) {
-      const current = await t.core.invoke('get_history');
-      const history = JSON.parse(current);
-      history.unshift(entry);
-      await t.core.invoke('save_history', { data: JSON.stringify(history.slice(0, 100)) });
+      await t.core.invoke('append_session', { entry });
     } else {
       // Fallback for browser dev
       try {
@@ -124,11 +316,94 @@ export const tauriBridge = {
     }
   },
 
+  // ── Settings (stored in Rust; every window gets "settings-changed") ──
+
+  getSettings: async () => {
+    const t = getTauri();
+    if (!t) {
+      try { return JSON.parse(localStorage.getItem('meow-settings') || 'null'); } catch { return null; }
+    }
+    if (hasLegacySettings()) {
+      const legacy = takeLegacySettings(await t.core.invoke('list_pals').then((pals) => pals.length, () => 0));
+      try {
+        const settings = legacy ? await t.core.invoke('update_settings', { patch: legacy }) : null;
+        // Only once they're safely in Rust
+        LEGACY_SETTINGS_KEYS.forEach((key) => localStorage.removeItem(key));
+        if (settings) return settings;
+      } catch {
+        // Keep the old keys so the next load can try again
+      }
+    }
+    return t.core.invoke('get_settings');
+  },
+
+  // `patch` is a partial settings object, e.g. { timer: { minutes: 30 } }.
+  updateSettings: async (patch) => {
+    const t = getTauri();
+    if (t) return t.core.invoke('update_settings', { patch });
+    // Fallback for browser dev
+    try {
+      const current = JSON.parse(localStorage.getItem('meow-settings') || '{}');
+      const next = { ...current, ...patch };
+      for (const key of ['timer', 'eyeBreak']) {
+        if (patch[key]) next[key] = { ...current[key], ...patch[key] };
+      }
+      localStorage.setItem('meow-settings', JSON.stringify(next));
+      return next;
+    } catch { return null; }
+  },
+
+  onSettingsChanged: async (cb) => {
+    const t = getTauri();
+    if (t) return t.event.listen('settings-changed', (event) => cb(event.payload));
+    return () => {};
+  },
+
   openEyeBreak: ({ duration = 20, strict = false } = {}) => {
     const t = getTauri();
     if (t) t.core.invoke('open_eye_break', { duration, strict });
   },
 
+  // Bind a global shortcut (e.g. "Super+Shift+KeyF") to an action, or unbind
+  // it with null. Rejects with a message on conflicts.
+  setShortcut: async (action, accelerator) => {
+    const t = getTauri();
+    if (t) return t.core.invoke('set_shortcut', { action, accelerator });
+    return null;
+  },
+
+  runAction: (action) => {
+    const t = getTauri();
+    if (t) t.core.invoke('run_action', { action });
+  },
+
+  // Task name sent by `meow start --task`
+  onSetTask: async (cb) => {
+    const t = getTauri();
+    if (t) return t.event.listen('set-task', (event) => cb(event.payload));
+    return () => {};
+  },
+
+  onToggleMute: async (cb) => {
+    const t = getTauri();
+    if (t) return t.event.listen('toggle-mute', () => cb());
+    return () => {};
+  },
+
+  // Names of the connected monitors, for the pill placement setting
+  listMonitors: async () => {
+    const t = getTauri();
+    if (t) return t.core.invoke('list_monitors');
+    return [];
+  },
+
+  // Focus pals as { id, name, icon }, in the order settings.pal indexes
+  listPals: async () => {
+    const t = getTauri();
+    if (t) return t.core.invoke('list_pals');
+    return [];
+  },
+
   closeEyeBreak: () => {
     const t = getTauri();
     if (t) t.core.invoke('close_eye_break');
@@ -140,11 +415,44 @@ export const tauriBridge = {
     return () => {};
   },
 
+  // { task } — a recent task picked from the tray, or null for plain Start Focus
+  onTrayStartFocus: async (cb) => {
+    const t = getTauri();
+    if (t) return t.event.listen('tray-start-focus', (event) => cb(event.payload));
+    return () => {};
+  },
+
   onEyeBreakNow: async (cb) => {
     const t = getTauri();
     if (t) return t.event.listen('tray-eye-break-now', () => cb());
     return () => {};
   },
 
+  // Most recent webhook deliveries, newest first
+  listWebhookDeliveries: async (limit = 50) => {
+    const t = getTauri();
+    if (t) return t.core.invoke('list_webhook_deliveries', { limit });
+    return [];
+  },
+
+  onWebhookDelivery: async (cb) => {
+    const t = getTauri();
+    if (t) return t.event.listen('webhook-delivery', (event) => cb(event.payload));
+    return () => {};
+  },
+
+  // Most recent shell hook runs, newest first
+  listHookRuns: async (limit = 50) => {
+    const t = getTauri();
+    if (t) return t.core.invoke('list_hook_runs', { limit });
+    return [];
+  },
+
+  onHookRun: async (cb) => {
+    const t = getTauri();
+    if (t) return t.event.listen('hook-run', (event) => cb(event.payload));
+    return () => {};
+  },
+
   getTauri,
 };
@@ -0,0 +1,370 @@
+use crate::control::Request as ControlRequest;
+use crate::history::SessionFilter;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
+use std::io::{Cursor, Read, Write};
+use std::str::FromStr;
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex, Once};
+use std::thread::JoinHandle;
+use std::time::Duration;
+use tauri::{AppHandle, Listener, Url};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+/// Comment line sent on idle event streams so dead clients are noticed.
+const KEEP_ALIVE: Duration = Duration::from_secs(15);
+
+/// Largest request body we'll read.
+const MAX_BODY_BYTES: u64 = 64 * 1024;
+
+const DEFAULT_PAGE: usize = 50;
+const MAX_PAGE: usize = 500;
+
+/// How long a restart waits for the old listener to let go of the port.
+const REBIND_TIMEOUT: Duration = Duration::from_secs(2);
+const REBIND_POLL: Duration = Duration::from_millis(50);
+
+struct Running {
+    server: Arc<Server>,
+    port: u16,
+    /// The accept loop, holding the other reference to `server`.
+    thread: JoinHandle<()>,
+}
+
+/// The running server, if the API is enabled.
+static SERVER: Mutex<Option<Running>> = Mutex::new(None);
+
+/// Token requests are checked against. Swapped in place, so changing it
+/// doesn't restart the server.
+static TOKEN: Mutex<String> = Mutex::new(String::new());
+
+/// One sender per open `/events` stream.
+static SUBSCRIBERS: Mutex<Vec<Sender<String>>> = Mutex::new(Vec::new());
+
+static LISTEN_ONCE: Once = Once::new();
+
+/// The opt-in HTTP API for editors, status bars and scripts. It only ever
+/// listens on 127.0.0.1 and every request needs the token.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ApiSettings {
+    pub enabled: bool,
+    pub port: u16,
+    /// Bearer token. Generated when the API is enabled without one, so
+    /// clearing it issues a new token.
+    pub token: String,
+}
+
+impl Default for ApiSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 7420,
+            token: String::new(),
+        }
+    }
+}
+
+/// A fresh random token, hex encoded.
+pub fn generate_token() -> Result<String, String> {
+    let mut bytes = [0u8; 24];
+    getrandom::fill(&mut bytes).map_err(|e| format!("couldn't generate a token: {}", e))?;
+    Ok(bytes.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct StartBody {
+    task: Option<String>,
+    minutes: Option<u32>,
+}
+
+fn json_response<T: Serialize>(status: u16, body: &T) -> Response<Cursor<Vec<u8>>> {
+    let header = Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..]).unwrap();
+    Response::from_data(serde_json::to_vec(body).unwrap_or_default())
+        .with_status_code(status)
+        .with_header(header)
+}
+
+fn error_response(status: u16, message: &str) -> Response<Cursor<Vec<u8>>> {
+    json_response(status, &json!({ "error": message }))
+}
+
+/// Compare without bailing at the first differing byte.
+fn same_token(given: &str, token: &str) -> bool {
+    given.len() == token.len()
+        && given
+            .bytes()
+            .zip(token.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// `Authorization: Bearer <token>`, or `?token=` for clients like
+/// `EventSource` that can't set headers.
+fn authorized(request: &Request, url: &Url, token: &str) -> bool {
+    let header = request
+        .headers()
+        .iter()
+        .find(|h| h.field.equiv("Authorization"))
+        .and_then(|h| h.value.as_str().strip_prefix("Bearer "));
+    let query = url
+        .query_pairs()
+        .find(|(key, _)| key == "token")
+        .map(|(_, value)| value);
+    match (header, query) {
+        (Some(given), _) => same_token(given, token),
+        (None, Some(given)) => same_token(&given, token),
+        (None, None) => false,
+    }
+}
+
+fn read_body(request: &mut Request) -> Result<String, String> {
+    let mut body = String::new();
+    request
+        .as_reader()
+        .take(MAX_BODY_BYTES)
+        .read_to_string(&mut body)
+        .map_err(|e| e.to_string())?;
+    Ok(body)
+}
+
+/// Run a control request and answer with the resulting timer state.
+fn control(app: &AppHandle, request: ControlRequest) -> Response<Cursor<Vec<u8>>> {
+    match crate::control::handle(app, request) {
+        crate::control::Response { error: Some(e), .. } => error_response(400, &e),
+        crate::control::Response { state, .. } => json_response(200, &state),
+    }
+}
+
+fn start(app: &AppHandle, request: &mut Request) -> Response<Cursor<Vec<u8>>> {
+    let body = match read_body(request) {
+        Ok(body) => body,
+        Err(e) => return error_response(400, &e),
+    };
+    let body: StartBody = if body.trim().is_empty() {
+        StartBody::default()
+    } else {
+        match serde_json::from_str(&body) {
+            Ok(body) => body,
+            Err(e) => return error_response(400, &format!("bad request body: {}", e)),
+        }
+    };
+    control(
+        app,
+        ControlRequest::Start {
+            task: body.task,
+            minutes: body.minutes,
+        },
+    )
+}
+
+/// Parse query parameter `key`, if present.
+fn param<T: FromStr>(query: &HashMap<String, String>, key: &str) -> Result<Option<T>, String>
+where
+    T::Err: std::fmt::Display,
+{
+    query
+        .get(key)
+        .map(|value| value.parse().map_err(|e| format!("invalid {}: {}", key, e)))
+        .transpose()
+}
+
+/// Offset, limit and filter from `?offset=&limit=&task=&from=&to=`.
+fn session_query(url: &Url) -> Result<(usize, usize, SessionFilter), String> {
+    let query: HashMap<String, String> = url.query_pairs().into_owned().collect();
+    let offset = param(&query, "offset")?.unwrap_or(0);
+    let limit = param(&query, "limit")?
+        .unwrap_or(DEFAULT_PAGE)
+        .min(MAX_PAGE);
+    let filter = SessionFilter {
+        task: query.get("task").cloned(),
+        from: param(&query, "from")?,
+        to: param(&query, "to")?,
+        ..Default::default()
+    };
+    Ok((offset, limit, filter))
+}
+
+/// `GET /sessions`, newest first.
+fn sessions(url: &Url) -> Response<Cursor<Vec<u8>>> {
+    let (offset, limit, filter) = match session_query(url) {
+        Ok(query) => query,
+        Err(e) => return error_response(400, &e),
+    };
+    match crate::history::list(offset, limit, &filter) {
+        Ok(page) => json_response(200, &page),
+        Err(e) => error_response(500, &e),
+    }
+}
+
+/// Hold the connection open and relay "timer-phase-change" as Server-Sent
+/// Events until the client goes away or the server stops.
+fn stream_events(request: Request) {
+    let (tx, rx) = mpsc::channel();
+    SUBSCRIBERS.lock().unwrap().push(tx);
+
+    // tiny_http buffers chunked bodies, so write the stream by hand to get
+    // each event out as it happens.
+    let mut out = request.into_writer();
+    let head = "HTTP/1.1 200 OK\r\n\
+                Content-Type: text/event-stream\r\n\
+                Cache-Control: no-cache\r\n\
+                Connection: close\r\n\r\n";
+    if out
+        .write_all(head.as_bytes())
+        .and_then(|_| out.flush())
+        .is_err()
+    {
+        return;
+    }
+    loop {
+        let chunk = match rx.recv_timeout(KEEP_ALIVE) {
+            Ok(payload) => format!("event: phase-change\ndata: {}\n\n", payload),
+            Err(RecvTimeoutError::Timeout) => ": keep-alive\n\n".to_string(),
+            Err(RecvTimeoutError::Disconnected) => break,
+        };
+        if out
+            .write_all(chunk.as_bytes())
+            .and_then(|_| out.flush())
+            .is_err()
+        {
+            break;
+        }
+    }
+}
+
+fn handle(app: &AppHandle, mut request: Request) {
+    // Only the path and query matter; the host is always us.
+    let Ok(url) = Url::parse("http://127.0.0.1").and_then(|base| base.join(request.url())) else {
+        let _ = request.respond(error_response(400, "bad request"));
+        return;
+    };
+    let token = TOKEN.lock().unwrap().clone();
+    if !authorized(&request, &url, &token) {
+        let _ = request.respond(error_response(401, "missing or wrong token"));
+        return;
+    }
+
+    let method = request.method().clone();
+    let response = match (method, url.path()) {
+        (Method::Get, "/state") => json_response(200, &crate::timer::state()),
+        (Method::Post, "/start") => start(app, &mut request),
+        (Method::Post, "/pause") => control(app, ControlRequest::Pause),
+        (Method::Get, "/sessions") => sessions(&url),
+        (Method::Get, "/events") => return stream_events(request),
+        _ => error_response(404, "not found"),
+    };
+    let _ = request.respond(response);
+}
+
+/// Stop the server, waiting for the accept loop to let go of it so the
+/// listening socket gets closed.
+fn stop() {
+    let running = SERVER.lock().unwrap().take();
+    if let Some(Running { server, thread, .. }) = running {
+        server.unblock();
+        let _ = thread.join();
+        drop(server);
+    }
+    // Dropping the senders ends every open event stream.
+    SUBSCRIBERS.lock().unwrap().clear();
+}
+
+/// Listen on `port`. tiny_http closes a dropped server's socket from its
+/// own thread, so right after a `stop` the port may still be held for a
+/// moment; keep trying for a while rather than fail.
+fn bind(port: u16) -> Result<Server, String> {
+    let started = std::time::Instant::now();
+    loop {
+        match Server::http(("127.0.0.1", port)) {
+            Ok(server) => return Ok(server),
+            Err(e) => {
+                let in_use = e
+                    .downcast_ref::<std::io::Error>()
+                    .is_some_and(|e| e.kind() == std::io::ErrorKind::AddrInUse);
+                if !in_use || started.elapsed() >= REBIND_TIMEOUT {
+                    return Err(format!("couldn't listen on port {}: {}", port, e));
+                }
+                std::thread::sleep(REBIND_POLL);
+            }
+        }
+    }
+}
+
+fn start_server(app: &AppHandle, settings: &ApiSettings) -> Result<(), String> {
+    if settings.token.is_empty() {
+        return Err("the HTTP API needs a token".to_string());
+    }
+    let server = Arc::new(bind(settings.port)?);
+    *TOKEN.lock().unwrap() = settings.token.clone();
+
+    let app = app.clone();
+    let accepting = server.clone();
+    let thread = std::thread::spawn(move || {
+        // Ends once `stop` unblocks the server.
+        for request in accepting.incoming_requests() {
+            let app = app.clone();
+            std::thread::spawn(move || handle(&app, request));
+        }
+    });
+    *SERVER.lock().unwrap() = Some(Running {
+        server,
+        port: settings.port,
+        thread,
+    });
+    log::info!("HTTP API listening on 127.0.0.1:{}", settings.port);
+    Ok(())
+}
+
+/// Start, restart or stop the server to match `settings`. If the new
+/// settings can't be served (e.g. the port is taken), the previous ones are
+/// restored and the error returned.
+pub fn apply(
+    app: &AppHandle,
+    settings: &ApiSettings,
+    previous: &ApiSettings,
+) -> Result<(), String> {
+    LISTEN_ONCE.call_once(|| {
+        app.listen_any("timer-phase-change", |event| {
+            let payload = event.payload().to_string();
+            SUBSCRIBERS
+                .lock()
+                .unwrap()
+                .retain(|tx| tx.send(payload.clone()).is_ok());
+        });
+    });
+
+    // Only the token changed: swap it without dropping the port.
+    let running_on = SERVER.lock().unwrap().as_ref().map(|running| running.port);
+    if settings.enabled && running_on == Some(settings.port) && !settings.token.is_empty() {
+        let mut token = TOKEN.lock().unwrap();
+        if *token != settings.token {
+            *token = settings.token.clone();
+            // Event streams opened with the old token end here.
+            SUBSCRIBERS.lock().unwrap().clear();
+        }
+        return Ok(());
+    }
+
+    stop();
+    if !settings.enabled {
+        return Ok(());
+    }
+    start_server(app, settings).inspect_err(|_| {
+        if previous.enabled {
+            if let Err(e) = start_server(app, previous) {
+                log::warn!("failed to restore the previous HTTP API: {}", e);
+            }
+        }
+    })
+}
+
+/// Start the server at launch if it's enabled.
+pub fn init(app: &AppHandle) {
+    let settings = crate::settings::load().api;
+    if let Err(e) = apply(app, &settings, &ApiSettings::default()) {
+        log::warn!("{}", e);
+    }
+}
@@ -0,0 +1,161 @@
+use crate::control::{Request, Response};
+use crate::timer::{Phase, TimerState};
+
+const USAGE: &str = "\
+Usage: meow <command> [options]
+
+Commands:
+  start [--task <name>] [--minutes <n>]   Start or resume the timer
+  pause                                   Pause the timer
+  reset                                   Stop and rewind the current phase
+  skip                                    Skip to the next phase
+  status [--json]                         Show the timer state
+  toggle                                  Expand or collapse the popover
+  history                                 Open the history window
+  eye-break                               Start an eye break now
+  quit                                    Quit meow
+
+Run without a command to launch the app.";
+
+/// Subcommands that make `meow` act as a client of the running app instead of
+/// launching it.
+const COMMANDS: &[&str] = &[
+    "start",
+    "pause",
+    "reset",
+    "skip",
+    "status",
+    "toggle",
+    "history",
+    "eye-break",
+    "quit",
+    "help",
+    "--help",
+    "-h",
+];
+
+fn parse(args: &[String]) -> Result<(Request, bool), String> {
+    let (command, rest) = args.split_first().ok_or(USAGE)?;
+    let mut task = None;
+    let mut minutes = None;
+    let mut json = false;
+    let mut rest = rest.iter();
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--task" if command == "start" => {
+                task = Some(rest.next().ok_or("--task needs a value")?.clone());
+            }
+            "--minutes" if command == "start" => {
+                let value = rest.next().ok_or("--minutes needs a value")?;
+                minutes = Some(
+                    value
+                        .parse()
+                        .map_err(|_| format!("invalid --minutes: {}", value))?,
+                );
+            }
+            "--json" => json = true,
+            _ => return Err(format!("unexpected argument: {}\n\n{}", arg, USAGE)),
+        }
+    }
+    let request = match command.as_str() {
+        "start" => Request::Start { task, minutes },
+        "pause" => Request::Pause,
+        "reset" => Request::Reset,
+        "skip" => Request::Skip,
+        "status" => Request::Status,
+        "toggle" => Request::Toggle,
+        "history" => Request::History,
+        "eye-break" => Request::EyeBreak,
+        "quit" => Request::Quit,
+        _ => return Err(USAGE.to_string()),
+    };
+    Ok((request, json))
+}
+
+#[cfg(unix)]
+fn send(request: &Request) -> Result<Response, String> {
+    use std::io::{BufRead, BufReader, Write};
+    use std::os::unix::net::UnixStream;
+
+    let path = crate::control::socket_path();
+    let mut stream = UnixStream::connect(&path).map_err(|_| "meow isn't running".to_string())?;
+    let mut line = serde_json::to_string(request).map_err(|e| e.to_string())?;
+    line.push('\n');
+    stream
+        .write_all(line.as_bytes())
+        .map_err(|e| e.to_string())?;
+
+    let mut reply = String::new();
+    BufReader::new(stream)
+        .read_line(&mut reply)
+        .map_err(|e| e.to_string())?;
+    serde_json::from_str(&reply).map_err(|e| format!("bad response from meow: {}", e))
+}
+
+#[cfg(not(unix))]
+fn send(_request: &Request) -> Result<Response, String> {
+    Err("the meow CLI is only available on macOS and Linux".to_string())
+}
+
+fn describe(state: &TimerState) -> String {
+    let phase = match state.phase {
+        Phase::Work if state.pomodoro_mode => "Work",
+        Phase::Work => "Focus",
+        Phase::ShortBreak => "Short break",
+        Phase::LongBreak => "Long break",
+    };
+    let status = if state.is_running {
+        "running"
+    } else {
+        "paused"
+    };
+    format!(
+        "{} · {} · {}:{:02} left · {} sessions done",
+        phase,
+        status,
+        state.time_left / 60,
+        state.time_left % 60,
+        state.completed_sessions
+    )
+}
+
+/// Handle `meow <command>` from the terminal. Returns the exit code, or
+/// `None` when the arguments aren't a CLI command and the app should launch.
+pub fn run(args: &[String]) -> Option<i32> {
+    let command = args.first()?;
+    if !COMMANDS.contains(&command.as_str()) {
+        return None;
+    }
+    if matches!(command.as_str(), "help" | "--help" | "-h") {
+        println!("{}", USAGE);
+        return Some(0);
+    }
+
+    let (request, json) = match parse(args) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            eprintln!("{}", e);
+            return Some(2);
+        }
+    };
+    match send(&request) {
+        Ok(Response { error: Some(e), .. }) => {
+            eprintln!("meow: {}", e);
+            Some(1)
+        }
+        Ok(Response { state, .. }) => {
+            match (state, json) {
+                (Some(state), true) => {
+                    println!("{}", serde_json::to_string(&state).unwrap_or_default())
+                }
+                (Some(state), false) => println!("{}", describe(&state)),
+                (None, _) => {}
+            }
+            Some(0)
+        }
+        Err(e) => {
+            eprintln!("meow: {}", e);
+            Some(1)
+        }
+    }
+}
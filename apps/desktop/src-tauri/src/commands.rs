@@ -1,5 +1,16 @@
+use crate::export::ExportFormat;
+use crate::history::{DateRange, SessionEntry, SessionFilter, SessionPage};
+use crate::hooks::HookRun;
+use crate::import::{ImportFormat, ImportReport};
+use crate::mouse_tracker::TrackerStats;
+use crate::settings::{Pal, Settings};
+use crate::shortcuts::Action;
+use crate::stats::{GroupBy, Stats};
+use crate::tasks::Task;
+use crate::timer::{TimerConfig, TimerState};
+use crate::webhooks::Delivery;
+use std::path::PathBuf;
 use tauri::{AppHandle, Manager};
-use tauri_plugin_global_shortcut::{Code, GlobalShortcutExt, Modifiers, Shortcut};
 use tauri_plugin_notification::NotificationExt;
 
 /// Resize the popover window height (clamped 45-600).
@@ -55,46 +66,192 @@ pub async fn window_close(app: AppHandle) -> Result<(), String> {
 /// the WebView's <input> elements can receive typing.
 #[tauri::command]
 pub async fn focus_window(app: AppHandle) -> Result<(), String> {
-    #[cfg(target_os = "macos")]
-    crate::platform::activate_app_for_input();
     if let Some(win) = app.get_webview_window("popover") {
+        #[cfg(any(target_os = "macos", target_os = "linux"))]
+        crate::platform::activate_app_for_input(&win);
         win.set_focus().map_err(|e| e.to_string())?;
     }
     Ok(())
 }
 
-/// Get the path to the history JSON file in the app's data directory.
-fn history_path(app: &AppHandle) -> Result<std::path::PathBuf, String> {
-    let dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
-    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
-    Ok(dir.join("history.json"))
+/// Report the cursor entering or leaving the popover webview, for platforms
+/// where the global cursor position can't be read.
+#[tauri::command]
+pub fn set_pointer_inside(inside: bool) {
+    crate::mouse_tracker::set_pointer_inside(inside);
 }
 
-/// Read session history from disk.
+/// Hover tracker wakeups per minute and current poll interval.
 #[tauri::command]
-pub async fn get_history(app: AppHandle) -> Result<String, String> {
-    let path = history_path(&app)?;
-    match std::fs::read_to_string(&path) {
-        Ok(data) => Ok(data),
-        Err(_) => Ok("[]".to_string()),
-    }
+pub fn get_tracker_stats() -> TrackerStats {
+    crate::mouse_tracker::stats()
+}
+
+/// Start (or resume) the timer, optionally applying new durations first.
+#[tauri::command]
+pub async fn start_timer(
+    app: AppHandle,
+    config: Option<TimerConfig>,
+) -> Result<TimerState, String> {
+    Ok(crate::timer::start(&app, config))
+}
+
+/// Pause the running timer.
+#[tauri::command]
+pub async fn pause_timer(app: AppHandle) -> Result<TimerState, String> {
+    Ok(crate::timer::pause(&app))
+}
+
+/// Resume a focus session paused for idleness, keeping the time away as
+/// focus or discarding the idle minutes.
+#[tauri::command]
+pub async fn resolve_idle(app: AppHandle, keep: bool) -> Result<TimerState, String> {
+    crate::timer::resolve_idle(&app, keep)
+}
+
+/// Stop and rewind the current phase, optionally applying new durations.
+#[tauri::command]
+pub async fn reset_timer(
+    app: AppHandle,
+    config: Option<TimerConfig>,
+) -> Result<TimerState, String> {
+    Ok(crate::timer::reset(&app, config))
+}
+
+/// Skip to the next phase (work → break → work) without counting the current one.
+#[tauri::command]
+pub async fn skip_phase(app: AppHandle) -> Result<TimerState, String> {
+    Ok(crate::timer::skip(&app))
+}
+
+/// Get the current timer state.
+#[tauri::command]
+pub async fn get_timer_state() -> Result<TimerState, String> {
+    Ok(crate::timer::state())
+}
+
+/// Record a finished session in the history store.
+#[tauri::command]
+pub async fn append_session(app: AppHandle, entry: SessionEntry) -> Result<(), String> {
+    crate::history::append(entry)?;
+    crate::tasks::changed(&app);
+    Ok(())
 }
 
-/// Save session history to disk.
+/// List recorded sessions, newest first, `limit` at a time.
 #[tauri::command]
-pub async fn save_history(app: AppHandle, data: String) -> Result<(), String> {
-    let path = history_path(&app)?;
-    std::fs::write(&path, data).map_err(|e| e.to_string())
+pub async fn list_sessions(
+    offset: usize,
+    limit: usize,
+    filters: Option<SessionFilter>,
+) -> Result<SessionPage, String> {
+    crate::history::list(offset, limit, &filters.unwrap_or_default())
 }
 
 /// Clear session history.
 #[tauri::command]
 pub async fn clear_history(app: AppHandle) -> Result<(), String> {
-    let path = history_path(&app)?;
-    std::fs::write(&path, "[]").map_err(|e| e.to_string())
+    crate::history::clear()?;
+    crate::tasks::changed(&app);
+    Ok(())
+}
+
+/// Aggregate focus time over the session history.
+#[tauri::command]
+pub async fn get_stats(
+    range: Option<DateRange>,
+    group_by: Option<GroupBy>,
+) -> Result<Stats, String> {
+    crate::stats::get(&range.unwrap_or_default(), group_by.unwrap_or_default())
+}
+
+/// Export session history as CSV, JSON or iCalendar. Without a `path`, a
+/// native save dialog asks where to write it. Returns the path written, or
+/// null if the dialog was cancelled.
+#[tauri::command]
+pub async fn export_history(
+    app: AppHandle,
+    format: ExportFormat,
+    range: Option<DateRange>,
+    path: Option<PathBuf>,
+) -> Result<Option<PathBuf>, String> {
+    crate::export::export(&app, format, &range.unwrap_or_default(), path)
+}
+
+/// Import sessions from another timer's CSV or JSON export. Without a
+/// `path`, a native open dialog asks for the file; the format defaults to
+/// the file extension. Returns null if the dialog was cancelled.
+#[tauri::command]
+pub async fn import_history(
+    app: AppHandle,
+    path: Option<PathBuf>,
+    format: Option<ImportFormat>,
+) -> Result<Option<ImportReport>, String> {
+    let report = crate::import::import(&app, path, format)?;
+    crate::tasks::changed(&app);
+    Ok(report)
+}
+
+/// List tasks with their completed pomodoros, newest first.
+#[tauri::command]
+pub async fn list_tasks(include_archived: Option<bool>) -> Result<Vec<Task>, String> {
+    crate::tasks::list(include_archived.unwrap_or(false))
+}
+
+/// Add a task, optionally with an estimate in pomodoros.
+#[tauri::command]
+pub async fn create_task(
+    app: AppHandle,
+    name: String,
+    estimate: Option<u32>,
+) -> Result<Task, String> {
+    let task = crate::tasks::create(&name, estimate)?;
+    crate::tasks::changed(&app);
+    Ok(task)
+}
+
+/// Rename a task and the sessions recorded against it.
+#[tauri::command]
+pub async fn rename_task(app: AppHandle, id: i64, name: String) -> Result<Task, String> {
+    let task = crate::tasks::rename(id, &name)?;
+    crate::tasks::changed(&app);
+    Ok(task)
 }
 
-/// Open a full-screen eye break overlay window.
+/// Set a task's estimate in pomodoros, or clear it with null.
+#[tauri::command]
+pub async fn set_task_estimate(
+    app: AppHandle,
+    id: i64,
+    estimate: Option<u32>,
+) -> Result<Task, String> {
+    let task = crate::tasks::set_estimate(id, estimate)?;
+    crate::tasks::changed(&app);
+    Ok(task)
+}
+
+/// Archive a task, or restore it with `archived: false`.
+#[tauri::command]
+pub async fn archive_task(app: AppHandle, id: i64, archived: bool) -> Result<Task, String> {
+    let task = crate::tasks::set_archived(id, archived)?;
+    crate::tasks::changed(&app);
+    Ok(task)
+}
+
+/// Get the current settings.
+#[tauri::command]
+pub async fn get_settings() -> Result<Settings, String> {
+    Ok(crate::settings::load())
+}
+
+/// Merge a partial settings object into the stored settings and broadcast
+/// the result to every window as "settings-changed".
+#[tauri::command]
+pub async fn update_settings(app: AppHandle, patch: serde_json::Value) -> Result<Settings, String> {
+    crate::settings::update(&app, patch)
+}
+
+/// Open a full-screen eye break overlay on every monitor.
 ///
 /// `strict` - when true, the overlay covers the menu bar and stays until the
 /// break timer ends; the frontend hides skip/snooze controls.
@@ -104,71 +261,52 @@ pub async fn open_eye_break(
     duration: u32,
     strict: bool,
 ) -> Result<(), String> {
-    use tauri::webview::WebviewWindowBuilder;
+    crate::windows::open_eye_break(&app, duration, strict).map_err(|e| e.to_string())
+}
 
-    // If already open, just focus it
-    if let Some(win) = app.get_webview_window("eyebreak") {
-        win.set_focus().map_err(|e| e.to_string())?;
-        return Ok(());
-    }
+/// Close the eye break overlay on every monitor.
+#[tauri::command]
+pub async fn close_eye_break(app: AppHandle) -> Result<(), String> {
+    crate::windows::close_eye_break(&app).map_err(|e| e.to_string())
+}
 
-    // Get the monitor size for full-screen overlay
-    let monitor = app
-        .primary_monitor()
-        .map_err(|e| e.to_string())?
-        .ok_or("No primary monitor")?;
-    let size = monitor.size();
-    let scale = monitor.scale_factor();
-    let w = size.width as f64 / scale;
-    let h = size.height as f64 / scale;
-
-    // Pass duration + strict to the overlay via URL query params
-    let url = format!("eyebreak?duration={}&strict={}", duration, strict);
-
-    let win = WebviewWindowBuilder::new(
-        &app,
-        "eyebreak",
-        tauri::WebviewUrl::App(url.into()),
-    )
-    .title("")
-    .inner_size(w, h)
-    .position(0.0, 0.0)
-    .decorations(false)
-    .resizable(false)
-    .always_on_top(true)
-    .transparent(true)
-    .skip_taskbar(true)
-    .focused(true)
-    .build()
-    .map_err(|e| e.to_string())?;
-
-    // In strict mode on macOS, raise above the menu bar so the user can't
-    // easily click away. Otherwise leave at normal always-on-top level.
-    #[cfg(target_os = "macos")]
-    {
-        if strict {
-            crate::platform::set_above_menu_bar(&win);
-            crate::platform::activate_app_for_input();
-        }
-    }
+/// Names of the connected monitors, for the pill placement setting.
+#[tauri::command]
+pub fn list_monitors(app: AppHandle) -> Result<Vec<String>, String> {
+    crate::windows::monitor_names(&app).map_err(|e| e.to_string())
+}
 
-    Ok(())
+/// The focus pals `Settings::pal` picks from.
+#[tauri::command]
+pub fn list_pals() -> Vec<Pal> {
+    crate::settings::PALS.to_vec()
 }
 
-/// Close the eye break overlay window.
+/// Bind a global shortcut to an action, or unbind it when `accelerator` is
+/// null. Takes effect immediately; fails on a conflict with another action.
 #[tauri::command]
-pub async fn close_eye_break(app: AppHandle) -> Result<(), String> {
-    if let Some(win) = app.get_webview_window("eyebreak") {
-        win.close().map_err(|e| e.to_string())?;
-    }
-    Ok(())
+pub async fn set_shortcut(
+    app: AppHandle,
+    action: Action,
+    accelerator: Option<String>,
+) -> Result<Settings, String> {
+    crate::shortcuts::set(&app, action, accelerator)
 }
 
-/// Register the global Cmd+Shift+F shortcut to toggle the popover.
-pub fn register_shortcuts(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
-    let shortcut = Shortcut::new(Some(Modifiers::SUPER | Modifiers::SHIFT), Code::KeyF);
-    app.global_shortcut().on_shortcut(shortcut, |app, _shortcut, _event| {
-        let _ = crate::windows::toggle_popover(app);
-    })?;
-    Ok(())
+/// Perform a shortcut action directly, e.g. from a command palette.
+#[tauri::command]
+pub fn run_action(app: AppHandle, action: Action) {
+    crate::shortcuts::run(&app, action);
+}
+
+/// The most recent webhook deliveries, newest first.
+#[tauri::command]
+pub async fn list_webhook_deliveries(limit: Option<usize>) -> Result<Vec<Delivery>, String> {
+    crate::webhooks::deliveries(limit.unwrap_or(50))
+}
+
+/// The most recent shell hook runs, newest first.
+#[tauri::command]
+pub async fn list_hook_runs(limit: Option<usize>) -> Result<Vec<HookRun>, String> {
+    crate::hooks::runs(limit.unwrap_or(50))
 }
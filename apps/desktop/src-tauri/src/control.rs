@@ -0,0 +1,170 @@
+use crate::timer::TimerState;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tauri::{AppHandle, Emitter, Manager};
+
+/// One request per line on the control socket, answered by one `Response`
+/// line. Mirrors the tray menu's actions.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "camelCase")]
+pub enum Request {
+    /// Start or resume. `minutes` sets the length of a focus session that
+    /// hasn't begun yet, for that session only; `task` replaces the task
+    /// name in the popover.
+    Start {
+        task: Option<String>,
+        minutes: Option<u32>,
+    },
+    Pause,
+    Reset,
+    Skip,
+    Status,
+    /// Expand or collapse the popover.
+    Toggle,
+    History,
+    EyeBreak,
+    Quit,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Response {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Timer state after the request was handled.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<TimerState>,
+}
+
+impl Response {
+    pub fn error(message: String) -> Self {
+        Self {
+            error: Some(message),
+            state: None,
+        }
+    }
+}
+
+/// Where the running app listens: the per-user runtime directory on Linux,
+/// the per-user temp directory elsewhere.
+pub fn socket_path() -> PathBuf {
+    match std::env::var_os("XDG_RUNTIME_DIR") {
+        Some(dir) => PathBuf::from(dir).join("meow.sock"),
+        None => {
+            let user = std::env::var("USER").unwrap_or_default();
+            std::env::temp_dir().join(format!("meow-{}.sock", user))
+        }
+    }
+}
+
+/// Perform a request against the running app. Shared with `meow://` links.
+pub fn handle(app: &AppHandle, request: Request) -> Response {
+    let state = match request {
+        Request::Start { task, minutes } => {
+            if let Some(m) = minutes.filter(|m| !(1..=240).contains(m)) {
+                return Response::error(format!("minutes must be between 1 and 240, got {}", m));
+            }
+            if task
+                .as_ref()
+                .is_some_and(|t| t.chars().count() > crate::history::MAX_TASK_LEN)
+            {
+                return Response::error(format!(
+                    "task must be at most {} characters",
+                    crate::history::MAX_TASK_LEN
+                ));
+            }
+            let state = match minutes {
+                Some(minutes) => match crate::timer::start_for(app, minutes) {
+                    Ok(state) => state,
+                    Err(e) => return Response::error(e),
+                },
+                None => crate::timer::start(app, None),
+            };
+            if let Some(task) = task {
+                if let Some(w) = app.get_webview_window("popover") {
+                    let _ = w.emit("set-task", task);
+                }
+            }
+            state
+        }
+        Request::Pause => crate::timer::pause(app),
+        Request::Reset => crate::timer::reset(app, None),
+        Request::Skip => crate::timer::skip(app),
+        Request::Status => crate::timer::state(),
+        Request::Toggle => {
+            let _ = crate::windows::toggle_popover(app);
+            crate::timer::state()
+        }
+        Request::History => {
+            crate::windows::open_history(app);
+            crate::timer::state()
+        }
+        Request::EyeBreak => {
+            if let Some(w) = app.get_webview_window("popover") {
+                let _ = w.emit("tray-eye-break-now", ());
+            }
+            crate::timer::state()
+        }
+        Request::Quit => {
+            // Give the response a moment to reach the CLI before exiting.
+            let app = app.clone();
+            std::thread::spawn(move || {
+                std::thread::sleep(std::time::Duration::from_millis(100));
+                app.exit(0);
+            });
+            crate::timer::state()
+        }
+    };
+    Response {
+        error: None,
+        state: Some(state),
+    }
+}
+
+/// Listen on the control socket for the `meow` CLI. If another instance is
+/// already answering on it, leave it alone.
+#[cfg(unix)]
+pub fn start(app: AppHandle) -> Result<(), Box<dyn std::error::Error>> {
+    use std::io::{BufRead, BufReader, Write};
+    use std::os::unix::fs::PermissionsExt;
+    use std::os::unix::net::{UnixListener, UnixStream};
+
+    let path = socket_path();
+    if UnixStream::connect(&path).is_ok() {
+        log::warn!("{} is in use by another instance", path.display());
+        return Ok(());
+    }
+    // Left behind by a previous run that didn't exit cleanly.
+    let _ = std::fs::remove_file(&path);
+    let listener = UnixListener::bind(&path)?;
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let app = app.clone();
+            std::thread::spawn(move || {
+                let Ok(reader) = stream.try_clone() else {
+                    return;
+                };
+                let mut writer = stream;
+                for line in BufReader::new(reader).lines() {
+                    let Ok(line) = line else {
+                        break;
+                    };
+                    let response = match serde_json::from_str::<Request>(&line) {
+                        Ok(request) => handle(&app, request),
+                        Err(e) => Response::error(format!("bad request: {}", e)),
+                    };
+                    let Ok(mut out) = serde_json::to_string(&response) else {
+                        break;
+                    };
+                    out.push('\n');
+                    if writer.write_all(out.as_bytes()).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+    });
+    Ok(())
+}
@@ -0,0 +1,123 @@
+use rusqlite::{Connection, OptionalExtension};
+use std::sync::Mutex;
+use tauri::Manager;
+
+/// The app's single SQLite connection, opened in `init` during setup.
+static DB: Mutex<Option<Connection>> = Mutex::new(None);
+
+/// Schema migrations, applied in order. `PRAGMA user_version` records how many
+/// have run, so append new entries — never edit or reorder existing ones.
+const MIGRATIONS: &[&str] = &[
+    // 1: sessions, tasks and settings
+    "CREATE TABLE sessions (
+        id          INTEGER PRIMARY KEY AUTOINCREMENT,
+        kind        TEXT NOT NULL,
+        duration    INTEGER NOT NULL,
+        task        TEXT NOT NULL DEFAULT '',
+        pal         TEXT NOT NULL DEFAULT '',
+        date        TEXT NOT NULL,
+        completed   INTEGER NOT NULL DEFAULT 1
+    );
+    CREATE INDEX sessions_date ON sessions(date);
+
+    CREATE TABLE tasks (
+        id          INTEGER PRIMARY KEY AUTOINCREMENT,
+        name        TEXT NOT NULL UNIQUE,
+        created_at  TEXT NOT NULL
+    );
+
+    CREATE TABLE settings (
+        key         TEXT PRIMARY KEY,
+        value       TEXT NOT NULL
+    );",
+    // 2: webhook delivery log
+    "CREATE TABLE webhook_deliveries (
+        id          INTEGER PRIMARY KEY AUTOINCREMENT,
+        url         TEXT NOT NULL,
+        event       TEXT NOT NULL,
+        attempts    INTEGER NOT NULL,
+        status      INTEGER,
+        error       TEXT,
+        date        TEXT NOT NULL
+    );",
+    // 3: shell hook log
+    "CREATE TABLE hook_runs (
+        id          INTEGER PRIMARY KEY AUTOINCREMENT,
+        event       TEXT NOT NULL,
+        command     TEXT NOT NULL,
+        exit_code   INTEGER,
+        timed_out   INTEGER NOT NULL,
+        output      TEXT NOT NULL,
+        duration_ms INTEGER NOT NULL,
+        date        TEXT NOT NULL
+    );",
+    // 4: time away recorded on sessions
+    "ALTER TABLE sessions ADD COLUMN idle_seconds INTEGER NOT NULL DEFAULT 0;",
+    // 5: task estimates and archiving, sessions linked to tasks by id
+    "ALTER TABLE tasks ADD COLUMN estimate INTEGER;
+    ALTER TABLE tasks ADD COLUMN archived INTEGER NOT NULL DEFAULT 0;
+    ALTER TABLE sessions ADD COLUMN task_id INTEGER REFERENCES tasks(id);
+    UPDATE sessions SET task_id = (SELECT id FROM tasks WHERE tasks.name = sessions.task)
+        WHERE task != '';
+    CREATE INDEX sessions_task_id ON sessions(task_id);",
+];
+
+/// Open (or create) `meow.db` in the app data directory, bring its schema up
+/// to date and import the pre-SQLite `history.json` if one is still around.
+pub fn init(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = app.path().app_data_dir()?;
+    std::fs::create_dir_all(&dir)?;
+
+    let mut conn = Connection::open(dir.join("meow.db"))?;
+    conn.pragma_update(None, "journal_mode", "WAL")?;
+    conn.pragma_update(None, "foreign_keys", true)?;
+    migrate(&mut conn)?;
+
+    let legacy = dir.join("history.json");
+    if legacy.exists() {
+        let imported = crate::history::import_legacy(&mut conn, &legacy)?;
+        // Keep the old file around as a backup, but out of the way so the
+        // import never runs twice.
+        std::fs::rename(&legacy, dir.join("history.json.bak"))?;
+        log::info!("imported {} sessions from history.json", imported);
+    }
+
+    *DB.lock().unwrap() = Some(conn);
+    Ok(())
+}
+
+pub(crate) fn migrate(conn: &mut Connection) -> rusqlite::Result<()> {
+    let version: usize = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+    for (i, sql) in MIGRATIONS.iter().enumerate().skip(version) {
+        let tx = conn.transaction()?;
+        tx.execute_batch(sql)?;
+        tx.pragma_update(None, "user_version", i + 1)?;
+        tx.commit()?;
+    }
+    Ok(())
+}
+
+/// Run `f` against the shared connection.
+pub fn with_conn<T>(f: impl FnOnce(&mut Connection) -> rusqlite::Result<T>) -> Result<T, String> {
+    let mut guard = DB.lock().unwrap();
+    let conn = guard.as_mut().ok_or("database not initialized")?;
+    f(conn).map_err(|e| e.to_string())
+}
+
+/// Read a raw value from the settings table.
+pub fn get_setting(conn: &Connection, key: &str) -> rusqlite::Result<Option<String>> {
+    conn.query_row("SELECT value FROM settings WHERE key = ?1", [key], |row| {
+        row.get(0)
+    })
+    .optional()
+}
+
+/// Insert or replace a raw value in the settings table.
+pub fn set_setting(conn: &Connection, key: &str, value: &str) -> rusqlite::Result<()> {
+    conn.execute(
+        "INSERT INTO settings (key, value) VALUES (?1, ?2)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+        [key, value],
+    )?;
+    Ok(())
+}
@@ -0,0 +1,74 @@
+use crate::control::Request;
+use tauri::{AppHandle, Url};
+
+/// Turn a `meow://` link into the request it stands for, e.g.
+/// `meow://start?task=PR%20review&minutes=45` or `meow://break`.
+///
+/// Links can come from any web page, so only the tray's actions are routed
+/// (no `quit`) and unknown routes or parameters are rejected outright.
+pub fn parse(link: &str) -> Result<Request, String> {
+    let url = Url::parse(link).map_err(|e| format!("invalid link {}: {}", link, e))?;
+    if url.scheme() != "meow" {
+        return Err(format!("not a meow:// link: {}", link));
+    }
+    // `meow://start` puts the route in the host, `meow:start` in the path.
+    let route = url
+        .host_str()
+        .unwrap_or_else(|| url.path())
+        .trim_matches('/');
+
+    let mut task = None;
+    let mut minutes = None;
+    for (key, value) in url.query_pairs() {
+        match key.as_ref() {
+            "task" if route == "start" => task = Some(value.trim().to_string()),
+            "minutes" if route == "start" => {
+                minutes = Some(
+                    value
+                        .parse()
+                        .map_err(|_| format!("invalid minutes: {}", value))?,
+                );
+            }
+            _ => {
+                return Err(format!(
+                    "unexpected parameter \"{}\" for meow://{}",
+                    key, route
+                ))
+            }
+        }
+    }
+
+    Ok(match route {
+        "start" => Request::Start {
+            task: task.filter(|t| !t.is_empty()),
+            minutes,
+        },
+        "pause" => Request::Pause,
+        "reset" => Request::Reset,
+        "skip" => Request::Skip,
+        "break" => Request::EyeBreak,
+        "toggle" => Request::Toggle,
+        "history" => Request::History,
+        _ => return Err(format!("unknown link meow://{}", route)),
+    })
+}
+
+/// Handle a link the OS opened us with. Bad links are logged and dropped.
+pub fn open(app: &AppHandle, link: &str) {
+    let request = match parse(link) {
+        Ok(request) => request,
+        Err(e) => {
+            log::warn!("{}", e);
+            return;
+        }
+    };
+    // Show the pill for a start so it's clear the link did something.
+    let show = matches!(request, Request::Start { .. });
+    if let Some(e) = crate::control::handle(app, request).error {
+        log::warn!("{}: {}", link, e);
+        return;
+    }
+    if show {
+        let _ = crate::windows::show_popover(app, true);
+    }
+}
@@ -0,0 +1,148 @@
+use super::DoNotDisturb;
+use gtk::gio;
+use gtk::gio::prelude::*;
+use std::collections::HashMap;
+use zbus::blocking::Connection;
+use zbus::zvariant::Value;
+
+const GNOME_SCHEMA: &str = "org.gnome.desktop.notifications";
+const GNOME_KEY: &str = "show-banners";
+
+/// Set in the settings table while we have GNOME's banners turned off, so
+/// a crash mid-focus doesn't leave them off for good.
+const BANNERS_HIDDEN_KEY: &str = "dndBannersHidden";
+
+/// Everything that works on this desktop, tried together:
+///
+/// - `org.freedesktop.Notifications.Inhibit` (KDE Plasma and other servers
+///   implementing the inhibition extension). The server drops the
+///   inhibition by itself if our connection goes away.
+/// - GNOME Shell has no inhibit call, so its `show-banners` setting is
+///   switched off instead.
+#[derive(Default)]
+pub struct LinuxDnd {
+    /// Held open for as long as the inhibition should last.
+    inhibit: Option<(Connection, u32)>,
+    banners_hidden: bool,
+}
+
+fn inhibit() -> zbus::Result<(Connection, u32)> {
+    let conn = Connection::session()?;
+    let reply = conn.call_method(
+        Some("org.freedesktop.Notifications"),
+        "/org/freedesktop/Notifications",
+        Some("org.freedesktop.Notifications"),
+        "Inhibit",
+        &("meow", "Focus session", HashMap::<&str, Value>::new()),
+    )?;
+    let cookie: u32 = reply.body().deserialize()?;
+    Ok((conn, cookie))
+}
+
+fn uninhibit(conn: &Connection, cookie: u32) -> zbus::Result<()> {
+    conn.call_method(
+        Some("org.freedesktop.Notifications"),
+        "/org/freedesktop/Notifications",
+        Some("org.freedesktop.Notifications"),
+        "UnInhibit",
+        &(cookie,),
+    )?;
+    Ok(())
+}
+
+/// GNOME's notification settings, if this system has them. `Settings::new`
+/// aborts on an unknown schema, so look it up first.
+fn gnome_settings() -> Option<gio::Settings> {
+    let schema = gio::SettingsSchemaSource::default()?.lookup(GNOME_SCHEMA, true)?;
+    schema
+        .has_key(GNOME_KEY)
+        .then(|| gio::Settings::new(GNOME_SCHEMA))
+}
+
+fn set_banners(settings: &gio::Settings, show: bool) -> Result<(), String> {
+    settings
+        .set_boolean(GNOME_KEY, show)
+        .map_err(|e| e.to_string())?;
+    gio::Settings::sync();
+    Ok(())
+}
+
+fn remember_banners_hidden(hidden: bool) -> Result<(), String> {
+    let flag = if hidden { "1" } else { "0" };
+    crate::db::with_conn(|conn| crate::db::set_setting(conn, BANNERS_HIDDEN_KEY, flag))
+}
+
+/// Turn banners off, writing that down first so there's never a moment
+/// they're off without `recover` knowing to turn them back on.
+fn hide_banners(settings: &gio::Settings) -> Result<(), String> {
+    remember_banners_hidden(true)?;
+    if let Err(e) = set_banners(settings, false) {
+        let _ = remember_banners_hidden(false);
+        return Err(e);
+    }
+    Ok(())
+}
+
+/// Turn banners back on, and only once that worked forget they were off.
+fn show_banners(settings: &gio::Settings) -> Result<(), String> {
+    set_banners(settings, true)?;
+    remember_banners_hidden(false)
+}
+
+impl DoNotDisturb for LinuxDnd {
+    fn enable(&mut self) -> Result<(), String> {
+        match inhibit() {
+            Ok(inhibit) => self.inhibit = Some(inhibit),
+            // Most servers (GNOME Shell, dunst, mako) don't implement it.
+            Err(e) => log::debug!("notification inhibit unavailable: {}", e),
+        }
+        if let Some(settings) = gnome_settings() {
+            // Leave it alone if the user already has banners off.
+            if settings.boolean(GNOME_KEY) {
+                hide_banners(&settings)?;
+                self.banners_hidden = true;
+            }
+        }
+        if self.inhibit.is_none() && !self.banners_hidden {
+            return Err("no supported notification server found".to_string());
+        }
+        Ok(())
+    }
+
+    fn restore(&mut self) -> Result<(), String> {
+        // Undo both even if one fails, so a broken D-Bus call doesn't leave
+        // GNOME's banners off.
+        let mut errors = Vec::new();
+        if let Some((conn, cookie)) = self.inhibit.take() {
+            if let Err(e) = uninhibit(&conn, cookie) {
+                errors.push(format!("uninhibit failed: {}", e));
+            }
+        }
+        if std::mem::take(&mut self.banners_hidden) {
+            if let Some(settings) = gnome_settings() {
+                if let Err(e) = show_banners(&settings) {
+                    errors.push(format!("couldn't show banners: {}", e));
+                }
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors.join("; "))
+        }
+    }
+}
+
+/// Turn GNOME's banners back on if a previous run hid them and never got
+/// the chance to restore them.
+pub fn recover() {
+    let hidden = crate::db::with_conn(|conn| crate::db::get_setting(conn, BANNERS_HIDDEN_KEY));
+    if !matches!(hidden, Ok(Some(ref flag)) if flag == "1") {
+        return;
+    }
+    if let Some(settings) = gnome_settings() {
+        if let Err(e) = show_banners(&settings) {
+            log::warn!("couldn't restore GNOME notification banners: {}", e);
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use crate::lifecycle::LifecycleEvent;
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+#[cfg(target_os = "linux")]
+mod linux;
+
+/// Silences other apps' notifications for the length of a focus session.
+/// One implementation per OS; `backend` picks it.
+pub trait DoNotDisturb: Send {
+    /// Silence notifications, remembering whatever needs restoring.
+    fn enable(&mut self) -> Result<(), String>;
+    /// Put notifications back the way `enable` found them.
+    fn restore(&mut self) -> Result<(), String>;
+}
+
+/// This OS's implementation, if there is one.
+fn backend() -> Option<Box<dyn DoNotDisturb>> {
+    #[cfg(target_os = "linux")]
+    {
+        Some(Box::new(linux::LinuxDnd::default()))
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        None
+    }
+}
+
+/// On or off, plus who to tell once it's done.
+type Request = (bool, Option<Sender<()>>);
+
+/// D-Bus calls can be slow, so they run on one worker thread (which also
+/// keeps enable/restore in order) rather than on the timer's threads.
+static WORKER: OnceLock<Mutex<Sender<Request>>> = OnceLock::new();
+
+fn worker() -> &'static Mutex<Sender<Request>> {
+    WORKER.get_or_init(|| {
+        let (tx, rx) = mpsc::channel::<Request>();
+        std::thread::spawn(move || {
+            let mut active: Option<Box<dyn DoNotDisturb>> = None;
+            for (enable, done) in rx {
+                match (enable, active.take()) {
+                    (true, Some(dnd)) => active = Some(dnd),
+                    (true, None) => {
+                        if let Some(mut dnd) = backend() {
+                            match dnd.enable() {
+                                Ok(()) => active = Some(dnd),
+                                Err(e) => log::warn!("couldn't turn on Do Not Disturb: {}", e),
+                            }
+                        }
+                    }
+                    (false, Some(mut dnd)) => {
+                        if let Err(e) = dnd.restore() {
+                            log::warn!("couldn't restore notifications: {}", e);
+                        }
+                    }
+                    (false, None) => {}
+                }
+                if let Some(done) = done {
+                    let _ = done.send(());
+                }
+            }
+        });
+        Mutex::new(tx)
+    })
+}
+
+/// Turn Do Not Disturb on or off. Repeats are ignored.
+pub fn set(active: bool) {
+    let _ = worker().lock().unwrap().send((active, None));
+}
+
+/// Restore notifications before the app exits, waiting (briefly) until done.
+pub fn shutdown() {
+    let (tx, rx) = mpsc::channel();
+    if worker().lock().unwrap().send((false, Some(tx))).is_ok() {
+        let _ = rx.recv_timeout(Duration::from_secs(2));
+    }
+}
+
+/// Follow focus sessions when the setting is on.
+pub fn on_event(event: LifecycleEvent) {
+    match event {
+        LifecycleEvent::FocusStart if crate::settings::load().focus_dnd => set(true),
+        LifecycleEvent::FocusComplete | LifecycleEvent::FocusStop => set(false),
+        _ => {}
+    }
+}
+
+/// Undo anything a previous run left behind, e.g. if it crashed mid-focus.
+pub fn init() {
+    #[cfg(target_os = "linux")]
+    linux::recover();
+}
@@ -0,0 +1,168 @@
+use crate::db;
+use crate::history::{self, DateRange, SessionEntry};
+use chrono::{Duration, Utc};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+use tauri_plugin_dialog::DialogExt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Csv,
+    /// Pretty-printed JSON array, in the same shape `list_sessions` returns.
+    Json,
+    /// iCalendar with one VEVENT per focus session (breaks are left out).
+    Ics,
+}
+
+impl ExportFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+            ExportFormat::Ics => "ics",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "CSV",
+            ExportFormat::Json => "JSON",
+            ExportFormat::Ics => "iCalendar",
+        }
+    }
+}
+
+/// Column order of CSV exports.
+const CSV_HEADER: [&str; 8] = [
+    "date",
+    "type",
+    "duration",
+    "task",
+    "pal",
+    "completed",
+    "id",
+    "idleSeconds",
+];
+
+fn to_csv(sessions: &[SessionEntry]) -> Result<Vec<u8>, String> {
+    let mut w = csv::Writer::from_writer(Vec::new());
+    w.write_record(CSV_HEADER).map_err(|e| e.to_string())?;
+    for s in sessions {
+        w.write_record([
+            history::format_date(&s.date),
+            s.kind.as_str().to_string(),
+            s.duration.to_string(),
+            s.task.clone(),
+            s.pal.clone(),
+            s.completed.to_string(),
+            s.id.map(|id| id.to_string()).unwrap_or_default(),
+            s.idle_seconds.to_string(),
+        ])
+        .map_err(|e| e.to_string())?;
+    }
+    w.into_inner().map_err(|e| e.to_string())
+}
+
+/// Escape a TEXT value per RFC 5545 §3.3.11.
+fn ics_escape(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+        .replace('\r', "")
+}
+
+/// Append a content line, folded so no line exceeds 75 octets (RFC 5545 §3.1).
+fn push_folded(out: &mut String, line: &str) {
+    let mut width = 0;
+    for c in line.chars() {
+        let len = c.len_utf8();
+        if width + len > 75 {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += len;
+    }
+    out.push_str("\r\n");
+}
+
+fn to_ics(sessions: &[SessionEntry]) -> String {
+    const STAMP: &str = "%Y%m%dT%H%M%SZ";
+    let now = Utc::now().format(STAMP).to_string();
+
+    let mut out = String::new();
+    for line in [
+        "BEGIN:VCALENDAR",
+        "VERSION:2.0",
+        "PRODID:-//meow//Focus Sessions//EN",
+        "CALSCALE:GREGORIAN",
+    ] {
+        push_folded(&mut out, line);
+    }
+    for s in sessions.iter().filter(|s| s.kind.is_focus()) {
+        // `date` is when the session ended.
+        let start = s.date - Duration::minutes(s.duration as i64);
+        let summary = if s.task.is_empty() {
+            "Focus session".to_string()
+        } else {
+            format!("Focus: {}", s.task)
+        };
+        let uid = match s.id {
+            Some(id) => format!("session-{}@meow", id),
+            None => format!("session-{}@meow", s.date.timestamp_millis()),
+        };
+        push_folded(&mut out, "BEGIN:VEVENT");
+        push_folded(&mut out, &format!("UID:{}", uid));
+        push_folded(&mut out, &format!("DTSTAMP:{}", now));
+        push_folded(&mut out, &format!("DTSTART:{}", start.format(STAMP)));
+        push_folded(&mut out, &format!("DTEND:{}", s.date.format(STAMP)));
+        push_folded(&mut out, &format!("SUMMARY:{}", ics_escape(&summary)));
+        if !s.completed {
+            push_folded(&mut out, "DESCRIPTION:Ended early");
+        }
+        push_folded(&mut out, "END:VEVENT");
+    }
+    push_folded(&mut out, "END:VCALENDAR");
+    out
+}
+
+/// Ask the user where to save, defaulting to e.g. "meow-history.csv".
+fn pick_path(app: &AppHandle, format: ExportFormat) -> Option<PathBuf> {
+    app.dialog()
+        .file()
+        .set_title("Export History")
+        .set_file_name(format!("meow-history.{}", format.extension()))
+        .add_filter(format.label(), &[format.extension()])
+        .blocking_save_file()
+        .and_then(|p| p.into_path().ok())
+}
+
+fn write(path: &Path, format: ExportFormat, sessions: &[SessionEntry]) -> Result<(), String> {
+    let data = match format {
+        ExportFormat::Csv => to_csv(sessions)?,
+        ExportFormat::Json => serde_json::to_vec_pretty(sessions).map_err(|e| e.to_string())?,
+        ExportFormat::Ics => to_ics(sessions).into_bytes(),
+    };
+    std::fs::write(path, data).map_err(|e| e.to_string())
+}
+
+/// Export sessions in `range` (oldest first) to `path`, or to a location
+/// picked in a native save dialog when no path is given. Returns the path
+/// written, or `None` if the dialog was cancelled.
+pub fn export(
+    app: &AppHandle,
+    format: ExportFormat,
+    range: &DateRange,
+    path: Option<PathBuf>,
+) -> Result<Option<PathBuf>, String> {
+    let path = match path.or_else(|| pick_path(app, format)) {
+        Some(path) => path,
+        None => return Ok(None),
+    };
+    let sessions = db::with_conn(|conn| history::query(conn, &range.filter()))?;
+    write(&path, format, &sessions)?;
+    Ok(Some(path))
+}
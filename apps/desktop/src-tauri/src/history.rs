@@ -0,0 +1,360 @@
+use crate::db;
+use chrono::{DateTime, SecondsFormat, Utc};
+use rusqlite::types::{Type, Value};
+use rusqlite::{params, params_from_iter, Connection, Row};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+const MAX_DURATION_MINUTES: u32 = 24 * 60;
+pub const MAX_TASK_LEN: usize = 500;
+const MAX_PAL_LEN: usize = 16;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SessionKind {
+    /// A plain (non-pomodoro) focus session.
+    Focus,
+    Work,
+    ShortBreak,
+    LongBreak,
+}
+
+impl SessionKind {
+    /// The name used in JSON and in the `sessions.kind` column.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SessionKind::Focus => "focus",
+            SessionKind::Work => "work",
+            SessionKind::ShortBreak => "shortBreak",
+            SessionKind::LongBreak => "longBreak",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "focus" => Some(SessionKind::Focus),
+            "work" => Some(SessionKind::Work),
+            "shortBreak" => Some(SessionKind::ShortBreak),
+            "longBreak" => Some(SessionKind::LongBreak),
+            _ => None,
+        }
+    }
+
+    /// Focus time (`focus` or `work`) as opposed to a break.
+    pub fn is_focus(self) -> bool {
+        matches!(self, SessionKind::Focus | SessionKind::Work)
+    }
+}
+
+/// One finished (or abandoned) timer session.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionEntry {
+    /// Row id in the session store; absent on entries not yet saved.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<i64>,
+    #[serde(rename = "type")]
+    pub kind: SessionKind,
+    /// Length in minutes.
+    pub duration: u32,
+    #[serde(default)]
+    pub task: String,
+    /// The task list entry this session counts towards. Sessions saved with
+    /// only a task name are linked to the task of that name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub task_id: Option<i64>,
+    /// Emoji of the focus pal that kept you company.
+    #[serde(default)]
+    pub pal: String,
+    /// When the session ended.
+    pub date: DateTime<Utc>,
+    /// False when the session was aborted before the timer ran out.
+    /// Entries written before this field existed were all completed.
+    #[serde(default = "default_completed")]
+    pub completed: bool,
+    /// Seconds the user was away from the computer during the session.
+    #[serde(default)]
+    pub idle_seconds: u64,
+}
+
+fn default_completed() -> bool {
+    true
+}
+
+impl SessionEntry {
+    /// Reject entries the UI could never have produced.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.duration == 0 || self.duration > MAX_DURATION_MINUTES {
+            return Err(format!(
+                "duration must be between 1 and {} minutes",
+                MAX_DURATION_MINUTES
+            ));
+        }
+        if self.task.chars().count() > MAX_TASK_LEN {
+            return Err(format!("task must be at most {} characters", MAX_TASK_LEN));
+        }
+        if self.pal.chars().count() > MAX_PAL_LEN {
+            return Err("pal is too long".to_string());
+        }
+        if self.date > Utc::now() + chrono::Duration::minutes(5) {
+            return Err("date is in the future".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Optional filters for `list_sessions`. All set fields must match.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SessionFilter {
+    #[serde(rename = "type")]
+    pub kind: Option<SessionKind>,
+    /// Only focus sessions (`focus` or `work`), ignoring breaks.
+    pub focus_only: bool,
+    /// Case-insensitive substring match on the task name.
+    pub task: Option<String>,
+    pub task_id: Option<i64>,
+    pub pal: Option<String>,
+    pub completed: Option<bool>,
+    /// Inclusive lower bound on `date`.
+    pub from: Option<DateTime<Utc>>,
+    /// Exclusive upper bound on `date`.
+    pub to: Option<DateTime<Utc>>,
+}
+
+/// A span of time to restrict sessions to. Unset bounds are open-ended.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DateRange {
+    /// Inclusive lower bound.
+    pub from: Option<DateTime<Utc>>,
+    /// Exclusive upper bound.
+    pub to: Option<DateTime<Utc>>,
+}
+
+impl DateRange {
+    /// A filter matching every session in this range.
+    pub fn filter(&self) -> SessionFilter {
+        SessionFilter {
+            from: self.from,
+            to: self.to,
+            ..Default::default()
+        }
+    }
+}
+
+/// A page of sessions, newest first, plus the number of matching sessions.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionPage {
+    pub sessions: Vec<SessionEntry>,
+    pub total: usize,
+}
+
+/// Column list matching `from_row`.
+const SESSION_COLUMNS: &str =
+    "id, kind, duration, task, pal, date, completed, idle_seconds, task_id";
+
+/// Dates are stored as fixed-width RFC 3339 UTC strings so that SQLite's text
+/// ordering is chronological.
+pub(crate) fn format_date(date: &DateTime<Utc>) -> String {
+    date.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+fn parse_date(idx: usize, s: &str) -> rusqlite::Result<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(s)
+        .map(|d| d.with_timezone(&Utc))
+        .map_err(|e| rusqlite::Error::FromSqlConversionFailure(idx, Type::Text, Box::new(e)))
+}
+
+fn from_row(row: &Row) -> rusqlite::Result<SessionEntry> {
+    let kind: String = row.get(1)?;
+    let date: String = row.get(5)?;
+    Ok(SessionEntry {
+        id: Some(row.get(0)?),
+        kind: SessionKind::from_str(&kind).ok_or_else(|| {
+            rusqlite::Error::FromSqlConversionFailure(1, Type::Text, kind.clone().into())
+        })?,
+        duration: row.get(2)?,
+        task: row.get(3)?,
+        pal: row.get(4)?,
+        date: parse_date(5, &date)?,
+        completed: row.get(6)?,
+        idle_seconds: row.get::<_, i64>(7)? as u64,
+        task_id: row.get(8)?,
+    })
+}
+
+/// Insert one session, linking it to its task (created by name if need be).
+/// Returns the new row id.
+pub(crate) fn insert(conn: &Connection, entry: &SessionEntry) -> rusqlite::Result<i64> {
+    let mut task = entry.task.trim().to_string();
+    let task_id = match entry.task_id {
+        // The task's current name wins over whatever the caller had.
+        Some(id) => {
+            task = conn.query_row("SELECT name FROM tasks WHERE id = ?1", [id], |row| {
+                row.get(0)
+            })?;
+            Some(id)
+        }
+        None if !task.is_empty() => {
+            conn.execute(
+                "INSERT OR IGNORE INTO tasks (name, created_at) VALUES (?1, ?2)",
+                params![task, format_date(&entry.date)],
+            )?;
+            Some(
+                conn.query_row("SELECT id FROM tasks WHERE name = ?1", [&task], |row| {
+                    row.get(0)
+                })?,
+            )
+        }
+        None => None,
+    };
+    conn.execute(
+        "INSERT INTO sessions (kind, duration, task, pal, date, completed, idle_seconds, task_id)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        params![
+            entry.kind.as_str(),
+            entry.duration,
+            task,
+            entry.pal,
+            format_date(&entry.date),
+            entry.completed,
+            entry.idle_seconds as i64,
+            task_id,
+        ],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+/// Import the JSON history the frontend used to write, in one transaction.
+/// Entries that don't parse or validate are skipped. Returns the number imported.
+pub(crate) fn import_legacy(conn: &mut Connection, path: &Path) -> Result<usize, String> {
+    let data = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let raw: Vec<serde_json::Value> = serde_json::from_str(&data).unwrap_or_default();
+    let entries: Vec<SessionEntry> = raw
+        .into_iter()
+        .filter_map(|v| serde_json::from_value::<SessionEntry>(v).ok())
+        .filter(|e| e.validate().is_ok())
+        .collect();
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    // The file was kept newest-first; insert oldest-first so ids follow time.
+    for entry in entries.iter().rev() {
+        insert(&tx, entry).map_err(|e| e.to_string())?;
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(entries.len())
+}
+
+/// Build the WHERE clause and parameters for `filter`.
+fn filter_sql(filter: &SessionFilter) -> (String, Vec<Value>) {
+    let mut clauses: Vec<&str> = Vec::new();
+    let mut values: Vec<Value> = Vec::new();
+    if let Some(kind) = filter.kind {
+        clauses.push("kind = ?");
+        values.push(kind.as_str().to_string().into());
+    }
+    if filter.focus_only {
+        clauses.push("kind IN ('focus', 'work')");
+    }
+    if let Some(task) = &filter.task {
+        clauses.push("instr(lower(task), lower(?)) > 0");
+        values.push(task.clone().into());
+    }
+    if let Some(task_id) = filter.task_id {
+        clauses.push("task_id = ?");
+        values.push(task_id.into());
+    }
+    if let Some(pal) = &filter.pal {
+        clauses.push("pal = ?");
+        values.push(pal.clone().into());
+    }
+    if let Some(completed) = filter.completed {
+        clauses.push("completed = ?");
+        values.push(i64::from(completed).into());
+    }
+    if let Some(from) = &filter.from {
+        clauses.push("date >= ?");
+        values.push(format_date(from).into());
+    }
+    if let Some(to) = &filter.to {
+        clauses.push("date < ?");
+        values.push(format_date(to).into());
+    }
+    let sql = if clauses.is_empty() {
+        String::new()
+    } else {
+        format!(" WHERE {}", clauses.join(" AND "))
+    };
+    (sql, values)
+}
+
+/// All sessions matching `filter`, oldest first.
+pub(crate) fn query(
+    conn: &Connection,
+    filter: &SessionFilter,
+) -> rusqlite::Result<Vec<SessionEntry>> {
+    let (where_sql, values) = filter_sql(filter);
+    let sql = format!(
+        "SELECT {} FROM sessions{} ORDER BY date ASC, id ASC",
+        SESSION_COLUMNS, where_sql
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(params_from_iter(values), from_row)?;
+    rows.collect()
+}
+
+/// Validate and record a session.
+pub fn append(entry: SessionEntry) -> Result<(), String> {
+    entry.validate()?;
+    db::with_conn(|conn| insert(conn, &entry).map(|_| ()))
+}
+
+/// Page through sessions matching `filter`, newest first.
+pub fn list(offset: usize, limit: usize, filter: &SessionFilter) -> Result<SessionPage, String> {
+    let (where_sql, values) = filter_sql(filter);
+    db::with_conn(|conn| {
+        let total: i64 = conn.query_row(
+            &format!("SELECT COUNT(*) FROM sessions{}", where_sql),
+            params_from_iter(values.iter()),
+            |row| row.get(0),
+        )?;
+
+        let mut page_values = values.clone();
+        page_values.push((limit as i64).into());
+        page_values.push((offset as i64).into());
+        let sql = format!(
+            "SELECT {} FROM sessions{} ORDER BY date DESC, id DESC LIMIT ? OFFSET ?",
+            SESSION_COLUMNS, where_sql
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        let sessions = stmt
+            .query_map(params_from_iter(page_values), from_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(SessionPage {
+            sessions,
+            total: total as usize,
+        })
+    })
+}
+
+/// Distinct task names, most recently used first, leaving out archived tasks.
+pub fn recent_tasks(limit: usize) -> Result<Vec<String>, String> {
+    db::with_conn(|conn| {
+        let mut stmt = conn.prepare(
+            "SELECT task FROM sessions
+             WHERE task != ''
+               AND (task_id IS NULL OR task_id NOT IN (SELECT id FROM tasks WHERE archived = 1))
+             GROUP BY task ORDER BY MAX(date) DESC LIMIT ?1",
+        )?;
+        let rows = stmt.query_map([limit as i64], |row| row.get(0))?;
+        rows.collect()
+    })
+}
+
+/// Delete every recorded session.
+pub fn clear() -> Result<(), String> {
+    db::with_conn(|conn| conn.execute("DELETE FROM sessions", []).map(|_| ()))
+}
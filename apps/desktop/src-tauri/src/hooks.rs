@@ -0,0 +1,412 @@
+use crate::db;
+use crate::history::format_date;
+use crate::lifecycle::{self, LifecycleEvent};
+use crate::timer::TimerState;
+use chrono::Utc;
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::process::{Child, Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+const MAX_COMMANDS: usize = 20;
+/// Output kept per run.
+const MAX_OUTPUT_BYTES: usize = 4096;
+/// Runs kept in the log.
+const LOG_SIZE: i64 = 200;
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// How long to keep reading output after the command exits.
+const OUTPUT_GRACE: Duration = Duration::from_millis(500);
+
+/// Shell commands to run on session lifecycle events.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HookSettings {
+    /// Commands per event, run one after another through the shell.
+    pub commands: BTreeMap<LifecycleEvent, Vec<String>>,
+    /// A command still running after this long is killed.
+    pub timeout_seconds: u32,
+}
+
+impl Default for HookSettings {
+    fn default() -> Self {
+        Self {
+            commands: BTreeMap::new(),
+            timeout_seconds: 30,
+        }
+    }
+}
+
+/// One entry in the hook log.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HookRun {
+    pub id: i64,
+    pub event: String,
+    pub command: String,
+    /// None if the command couldn't be started, timed out or was killed.
+    pub exit_code: Option<i32>,
+    pub timed_out: bool,
+    /// Combined stdout and stderr, truncated.
+    pub output: String,
+    pub duration_ms: u64,
+    pub date: String,
+}
+
+pub fn validate(hooks: &HookSettings) -> Result<(), String> {
+    if !(1..=600).contains(&hooks.timeout_seconds) {
+        return Err("hooks.timeoutSeconds must be between 1 and 600".to_string());
+    }
+    let total: usize = hooks.commands.values().map(Vec::len).sum();
+    if total > MAX_COMMANDS {
+        return Err(format!("at most {} hook commands", MAX_COMMANDS));
+    }
+    Ok(())
+}
+
+/// `timeLeft` -> `MEOW_TIME_LEFT`.
+fn env_name(field: &str) -> String {
+    let mut name = String::from("MEOW_");
+    for c in field.chars() {
+        if c.is_ascii_uppercase() {
+            name.push('_');
+        }
+        name.push(c.to_ascii_uppercase());
+    }
+    name
+}
+
+/// The `MEOW_*` variables a command sees for `event`. `state` describes the
+/// phase the event is about, e.g. the focus that just ended for
+/// `focusComplete`.
+fn env(event: LifecycleEvent, state: &TimerState) -> Vec<(String, String)> {
+    lifecycle::fields(event, state)
+        .into_iter()
+        .map(|(name, value)| (env_name(name), value))
+        .collect()
+}
+
+fn shell(command: &str) -> Command {
+    if cfg!(windows) {
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", command]);
+        cmd
+    } else {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", command]);
+        cmd
+    }
+}
+
+/// How a single command went.
+struct Outcome {
+    exit_code: Option<i32>,
+    timed_out: bool,
+    output: String,
+}
+
+/// Drain a child's pipe into `output` on its own thread, so a chatty
+/// command can't block on a full pipe while we wait for it.
+fn capture(
+    pipe: Option<impl Read + Send + 'static>,
+    output: Arc<Mutex<Vec<u8>>>,
+) -> std::thread::JoinHandle<()> {
+    std::thread::spawn(move || {
+        let Some(mut pipe) = pipe else {
+            return;
+        };
+        let mut buf = [0u8; 1024];
+        while let Ok(n @ 1..) = pipe.read(&mut buf) {
+            let mut output = output.lock().unwrap();
+            let room = MAX_OUTPUT_BYTES.saturating_sub(output.len());
+            output.extend_from_slice(&buf[..n.min(room)]);
+        }
+    })
+}
+
+fn kill(child: &mut Child) {
+    // The shell runs in its own process group; take its children down too.
+    #[cfg(unix)]
+    unsafe {
+        libc::killpg(child.id() as i32, libc::SIGKILL);
+    }
+    let _ = child.kill();
+}
+
+/// Run one command to completion or until `timeout`.
+fn execute(command: &str, env: &[(String, String)], timeout: Duration) -> Outcome {
+    let mut cmd = shell(command);
+    cmd.envs(env.iter().map(|(k, v)| (k, v)))
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(&mut cmd, 0);
+    let mut child = match cmd.spawn() {
+        Ok(child) => child,
+        Err(e) => {
+            return Outcome {
+                exit_code: None,
+                timed_out: false,
+                output: format!("couldn't start: {}", e),
+            }
+        }
+    };
+    let output = Arc::new(Mutex::new(Vec::new()));
+    let readers = [
+        capture(child.stdout.take(), output.clone()),
+        capture(child.stderr.take(), output.clone()),
+    ];
+
+    let deadline = Instant::now() + timeout;
+    let (status, timed_out) = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break (Some(status), false),
+            Ok(None) if Instant::now() >= deadline => {
+                kill(&mut child);
+                break (child.wait().ok(), true);
+            }
+            Ok(None) => std::thread::sleep(POLL_INTERVAL),
+            Err(e) => {
+                log::warn!("lost track of hook {}: {}", command, e);
+                break (None, false);
+            }
+        }
+    };
+    // Something the command started in the background (e.g. `slack &`) can
+    // hold the pipes open indefinitely, so only wait briefly for the rest.
+    let grace = Instant::now() + OUTPUT_GRACE;
+    while !readers.iter().all(|r| r.is_finished()) && Instant::now() < grace {
+        std::thread::sleep(POLL_INTERVAL);
+    }
+    let output = String::from_utf8_lossy(&output.lock().unwrap()).into_owned();
+    Outcome {
+        exit_code: if timed_out {
+            None
+        } else {
+            status.and_then(|s| s.code())
+        },
+        timed_out,
+        output,
+    }
+}
+
+fn log_run(
+    event: LifecycleEvent,
+    command: &str,
+    outcome: Outcome,
+    duration: Duration,
+) -> Result<HookRun, String> {
+    let Outcome {
+        exit_code,
+        timed_out,
+        output,
+    } = outcome;
+    let duration_ms = duration.as_millis() as u64;
+    let date = format_date(&Utc::now());
+    db::with_conn(|conn| {
+        conn.execute(
+            "INSERT INTO hook_runs (event, command, exit_code, timed_out, output, duration_ms, date)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                event.as_str(),
+                command,
+                exit_code,
+                timed_out,
+                output,
+                duration_ms as i64,
+                date
+            ],
+        )?;
+        let id = conn.last_insert_rowid();
+        conn.execute("DELETE FROM hook_runs WHERE id <= ?1", [id - LOG_SIZE])?;
+        Ok(HookRun {
+            id,
+            event: event.as_str().to_string(),
+            command: command.to_string(),
+            exit_code,
+            timed_out,
+            output,
+            duration_ms,
+            date,
+        })
+    })
+}
+
+/// Run the commands configured for `event` in order on a background thread,
+/// with `MEOW_*` environment variables describing the session. Each run is
+/// logged and broadcast as "hook-run".
+pub fn run(app: &AppHandle, event: LifecycleEvent, state: &TimerState) {
+    let settings = crate::settings::load().hooks;
+    let commands: Vec<String> = settings
+        .commands
+        .get(&event)
+        .into_iter()
+        .flatten()
+        .filter(|c| !c.trim().is_empty())
+        .cloned()
+        .collect();
+    if commands.is_empty() {
+        return;
+    }
+    let env = env(event, state);
+    let timeout = Duration::from_secs(settings.timeout_seconds as u64);
+
+    let app = app.clone();
+    std::thread::spawn(move || {
+        for command in commands {
+            let started = Instant::now();
+            let outcome = execute(&command, &env, timeout);
+            if outcome.timed_out {
+                log::warn!("hook timed out: {}", command);
+            }
+            match log_run(event, &command, outcome, started.elapsed()) {
+                Ok(run) => {
+                    let _ = app.emit("hook-run", &run);
+                }
+                Err(e) => log::warn!("failed to log hook run: {}", e),
+            }
+        }
+    });
+}
+
+/// The most recent hook runs, newest first.
+pub fn runs(limit: usize) -> Result<Vec<HookRun>, String> {
+    db::with_conn(|conn| {
+        let mut stmt = conn.prepare(
+            "SELECT id, event, command, exit_code, timed_out, output, duration_ms, date
+             FROM hook_runs ORDER BY id DESC LIMIT ?1",
+        )?;
+        let rows = stmt.query_map([limit as i64], |row| {
+            Ok(HookRun {
+                id: row.get(0)?,
+                event: row.get(1)?,
+                command: row.get(2)?,
+                exit_code: row.get(3)?,
+                timed_out: row.get(4)?,
+                output: row.get(5)?,
+                duration_ms: row.get::<_, i64>(6)? as u64,
+                date: row.get(7)?,
+            })
+        })?;
+        rows.collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::timer::{Phase, PhaseChange};
+    use std::path::PathBuf;
+
+    /// A scratch file for a command to write to, removed when dropped.
+    struct TempFile(PathBuf);
+
+    impl TempFile {
+        fn new(name: &str) -> Self {
+            let path =
+                std::env::temp_dir().join(format!("meow-hook-{}-{}", std::process::id(), name));
+            let _ = std::fs::remove_file(&path);
+            TempFile(path)
+        }
+
+        fn read(&self) -> String {
+            std::fs::read_to_string(&self.0).unwrap_or_default()
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+        }
+    }
+
+    /// What a focusComplete hook gets once a 50 minute focus has run out and
+    /// the timer has moved on to a short break.
+    fn focus_complete_env() -> Vec<(String, String)> {
+        let after = TimerState {
+            phase: Phase::ShortBreak,
+            is_running: false,
+            time_left: 300,
+            total_time: 300,
+            completed_sessions: 3,
+            pomodoro_mode: true,
+            idle_paused: false,
+        };
+        let change = PhaseChange {
+            from: Phase::Work,
+            to: Phase::ShortBreak,
+            completed: true,
+            completed_sessions: 3,
+            pomodoro_mode: true,
+            idle_seconds: 0,
+            minutes: 50,
+        };
+        let (event, ended) = change.lifecycle_event(&after).unwrap();
+        env(event, &ended)
+    }
+
+    fn var<'a>(env: &'a [(String, String)], name: &str) -> Option<&'a str> {
+        env.iter().find(|(k, _)| k == name).map(|(_, v)| v.as_str())
+    }
+
+    #[test]
+    fn env_describes_the_phase_that_ended() {
+        let env = focus_complete_env();
+        assert_eq!(var(&env, "MEOW_EVENT"), Some("focusComplete"));
+        assert_eq!(var(&env, "MEOW_PHASE"), Some("work"));
+        assert_eq!(var(&env, "MEOW_MINUTES"), Some("50"));
+        assert_eq!(var(&env, "MEOW_TOTAL_TIME"), Some("3000"));
+        assert_eq!(var(&env, "MEOW_TIME_LEFT"), Some("0"));
+        assert_eq!(var(&env, "MEOW_IS_RUNNING"), Some("false"));
+        assert_eq!(var(&env, "MEOW_COMPLETED_SESSIONS"), Some("3"));
+        assert!(var(&env, "MEOW_DATE").is_some());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn commands_run_with_the_env() {
+        let out = TempFile::new("env");
+        let mut env = focus_complete_env();
+        env.push(("OUT".to_string(), out.0.display().to_string()));
+        let outcome = execute(r#"env > "$OUT"; echo done"#, &env, Duration::from_secs(10));
+        assert_eq!(outcome.exit_code, Some(0));
+        assert!(!outcome.timed_out);
+        assert_eq!(outcome.output, "done\n");
+        let written = out.read();
+        assert!(written.lines().any(|l| l == "MEOW_PHASE=work"));
+        assert!(written.lines().any(|l| l == "MEOW_MINUTES=50"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn a_timed_out_command_is_killed_with_its_children() {
+        let out = TempFile::new("pid");
+        let env = [("OUT".to_string(), out.0.display().to_string())];
+        let started = Instant::now();
+        let outcome = execute(
+            r#"sleep 30 & echo $! > "$OUT"; wait"#,
+            &env,
+            Duration::from_millis(300),
+        );
+        assert!(outcome.timed_out);
+        assert_eq!(outcome.exit_code, None);
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        // The background sleep went down with the shell's process group.
+        let pid = out.read().trim().to_string();
+        assert!(!pid.is_empty());
+        let alive = || {
+            std::fs::read_to_string(format!("/proc/{}/stat", pid))
+                .is_ok_and(|stat| !stat.contains(") Z "))
+        };
+        let deadline = Instant::now() + Duration::from_secs(2);
+        while alive() && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        assert!(!alive());
+    }
+}
@@ -0,0 +1,98 @@
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+/// Input this recent means the user is back.
+const RETURN_WINDOW_SECS: u64 = 10;
+
+/// Pausing focus sessions while the user is away from the computer.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct IdleSettings {
+    pub enabled: bool,
+    /// Minutes without keyboard or mouse input before the timer pauses.
+    pub threshold_minutes: u32,
+}
+
+impl Default for IdleSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            threshold_minutes: 5,
+        }
+    }
+}
+
+/// Payload of the "idle-return" event.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct IdleReturn {
+    /// How long the user was away, counting from their last input.
+    idle_seconds: u64,
+}
+
+pub fn validate(idle: &IdleSettings) -> Result<(), String> {
+    if !(1..=120).contains(&idle.threshold_minutes) {
+        return Err("idle.thresholdMinutes must be between 1 and 120".to_string());
+    }
+    Ok(())
+}
+
+/// Seconds since the last keyboard or mouse input, if the OS can tell us.
+fn idle_seconds() -> Option<u64> {
+    #[cfg(any(target_os = "macos", target_os = "linux"))]
+    {
+        crate::platform::idle_seconds()
+    }
+    #[cfg(not(any(target_os = "macos", target_os = "linux")))]
+    {
+        None
+    }
+}
+
+/// Spawns the thread that pauses a running focus phase once the user has
+/// been idle past the threshold, and emits "idle-return" (expanding the
+/// popover) when they come back, so they can keep or discard the time away
+/// with `resolve_idle`.
+pub fn start(app: AppHandle) {
+    std::thread::spawn(move || {
+        // The idle pause the user has already been asked about.
+        let mut announced: Option<Instant> = None;
+        loop {
+            std::thread::sleep(POLL_INTERVAL);
+
+            if let Some(since) = crate::timer::idle_since() {
+                if announced == Some(since) {
+                    continue;
+                }
+                if idle_seconds().is_some_and(|idle| idle < RETURN_WINDOW_SECS) {
+                    announced = Some(since);
+                    let away = since.elapsed().as_secs();
+                    if let Err(e) = crate::windows::show_popover(&app, false) {
+                        log::warn!("failed to show popover: {}", e);
+                    }
+                    let _ = app.emit("idle-return", IdleReturn { idle_seconds: away });
+                }
+                continue;
+            }
+
+            let state = crate::timer::state();
+            if !state.is_running || state.phase != crate::timer::Phase::Work {
+                continue;
+            }
+            let settings = crate::settings::load().idle;
+            if !settings.enabled {
+                continue;
+            }
+            let Some(idle) = idle_seconds() else {
+                continue;
+            };
+            if idle >= settings.threshold_minutes as u64 * 60
+                && crate::timer::pause_idle(&app, Duration::from_secs(idle))
+            {
+                log::info!("paused focus after {} s idle", idle);
+            }
+        }
+    });
+}
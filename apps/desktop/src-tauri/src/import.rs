@@ -0,0 +1,392 @@
+use crate::db;
+use crate::history::{self, SessionEntry, SessionFilter, SessionKind};
+use chrono::{DateTime, Duration, Local, NaiveDateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+use tauri_plugin_dialog::DialogExt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportFormat {
+    Csv,
+    Json,
+}
+
+impl ImportFormat {
+    fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_ascii_lowercase().as_str() {
+            "csv" => Some(ImportFormat::Csv),
+            "json" => Some(ImportFormat::Json),
+            _ => None,
+        }
+    }
+}
+
+/// What happened to each row of an imported file.
+#[derive(Clone, Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportReport {
+    pub imported: usize,
+    /// Rows whose end time and kind match a session already in history.
+    pub skipped: usize,
+    /// Rows that couldn't be turned into a valid session.
+    pub malformed: usize,
+}
+
+// Column names other timers use for each field, compared case-insensitively
+// after stripping spaces, dashes and underscores. A bare "date" is only used
+// when it carries a time of day.
+const END_KEYS: &[&str] = &[
+    "end",
+    "endtime",
+    "endedat",
+    "enddate",
+    "finishedat",
+    "completedat",
+    "date",
+];
+const START_KEYS: &[&str] = &[
+    "start",
+    "starttime",
+    "startedat",
+    "startdate",
+    "begin",
+    "timestamp",
+];
+const MINUTES_KEYS: &[&str] = &["duration", "minutes", "durationminutes", "length", "mins"];
+const SECONDS_KEYS: &[&str] = &["seconds", "durationseconds", "durationsecs", "secs"];
+const TASK_KEYS: &[&str] = &[
+    "task",
+    "name",
+    "title",
+    "description",
+    "activity",
+    "label",
+    "project",
+];
+const KIND_KEYS: &[&str] = &["type", "kind", "mode", "sessiontype", "phase"];
+const COMPLETED_KEYS: &[&str] = &["completed", "status", "finished", "done"];
+const PAL_KEYS: &[&str] = &["pal"];
+// Keys a JSON export may keep its list of sessions under.
+const LIST_KEYS: &[&str] = &["sessions", "history", "entries", "data"];
+
+fn normalize_key(key: &str) -> String {
+    key.chars()
+        .filter(|c| !matches!(c, ' ' | '_' | '-'))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// First non-empty value under any of `keys`, as text.
+fn field(record: &Map<String, Value>, keys: &[&str]) -> Option<String> {
+    keys.iter().find_map(|k| match record.get(*k)? {
+        Value::String(s) if !s.trim().is_empty() => Some(s.trim().to_string()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    })
+}
+
+/// Parse RFC 3339, common "YYYY-MM-DD HH:MM[:SS]" forms (taken as local
+/// time), or a Unix timestamp in seconds or milliseconds.
+fn parse_date(s: &str) -> Option<DateTime<Utc>> {
+    if let Ok(d) = DateTime::parse_from_rfc3339(s) {
+        return Some(d.with_timezone(&Utc));
+    }
+    if let Ok(n) = s.parse::<i64>() {
+        // Anything past ~2286 in seconds is really milliseconds.
+        return if n > 9_999_999_999 {
+            DateTime::from_timestamp_millis(n)
+        } else {
+            DateTime::from_timestamp(n, 0)
+        };
+    }
+    const FORMATS: &[&str] = &[
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%d %H:%M",
+        "%Y-%m-%dT%H:%M:%S%.f",
+        "%Y-%m-%dT%H:%M",
+        "%Y/%m/%d %H:%M:%S",
+        "%Y/%m/%d %H:%M",
+        "%m/%d/%Y %H:%M:%S",
+        "%m/%d/%Y %H:%M",
+    ];
+    FORMATS.iter().find_map(|f| {
+        let naive = NaiveDateTime::parse_from_str(s, f).ok()?;
+        Local
+            .from_local_datetime(&naive)
+            .earliest()
+            .map(|d| d.with_timezone(&Utc))
+    })
+}
+
+/// First value under any of `keys` that parses as a point in time.
+fn date_field(record: &Map<String, Value>, keys: &[&str]) -> Option<DateTime<Utc>> {
+    keys.iter().find_map(|k| {
+        let s = field(record, &[k])?;
+        if *k == "date" && !s.contains(':') {
+            return None;
+        }
+        parse_date(&s)
+    })
+}
+
+/// Parse a length as plain seconds, or "H:MM:SS" / "MM:SS".
+fn parse_seconds(s: &str, unit_seconds: f64) -> Option<f64> {
+    if let Ok(n) = s.parse::<f64>() {
+        return Some(n * unit_seconds);
+    }
+    let parts: Vec<f64> = s
+        .split(':')
+        .map(|p| p.parse().ok())
+        .collect::<Option<_>>()?;
+    match parts.as_slice() {
+        [m, s] => Some(m * 60.0 + s),
+        [h, m, s] => Some(h * 3600.0 + m * 60.0 + s),
+        _ => None,
+    }
+}
+
+fn parse_kind(s: &str) -> SessionKind {
+    let s = s.to_lowercase();
+    if s.contains("long") {
+        SessionKind::LongBreak
+    } else if s.contains("break") || s.contains("rest") {
+        SessionKind::ShortBreak
+    } else if s.contains("work") || s.contains("pomodoro") {
+        SessionKind::Work
+    } else {
+        SessionKind::Focus
+    }
+}
+
+fn parse_completed(s: &str) -> bool {
+    !matches!(
+        s.to_lowercase().as_str(),
+        "false"
+            | "0"
+            | "no"
+            | "aborted"
+            | "interrupted"
+            | "cancelled"
+            | "canceled"
+            | "skipped"
+            | "incomplete"
+    )
+}
+
+/// Map one record (keys already normalized) to a session, or `None` if it
+/// lacks a usable time and duration.
+fn to_session(record: &Map<String, Value>) -> Option<SessionEntry> {
+    let end = date_field(record, END_KEYS);
+    let start = date_field(record, START_KEYS);
+
+    let seconds = field(record, SECONDS_KEYS)
+        .and_then(|s| parse_seconds(&s, 1.0))
+        .or_else(|| field(record, MINUTES_KEYS).and_then(|s| parse_seconds(&s, 60.0)))
+        .or_else(|| Some((end? - start?).num_seconds() as f64))?;
+    let duration = (seconds / 60.0).round() as u32;
+
+    let date = end.or_else(|| Some(start? + Duration::seconds(seconds as i64)))?;
+
+    let entry = SessionEntry {
+        id: None,
+        kind: field(record, KIND_KEYS).map_or(SessionKind::Focus, |s| parse_kind(&s)),
+        duration,
+        task: field(record, TASK_KEYS).unwrap_or_default(),
+        pal: field(record, PAL_KEYS).unwrap_or_default(),
+        date,
+        completed: field(record, COMPLETED_KEYS).is_none_or(|s| parse_completed(&s)),
+        idle_seconds: 0,
+        task_id: None,
+    };
+    entry.validate().ok()?;
+    Some(entry)
+}
+
+fn read_csv(path: &Path) -> Result<Vec<Option<Map<String, Value>>>, String> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_path(path)
+        .map_err(|e| e.to_string())?;
+    let headers: Vec<String> = reader
+        .headers()
+        .map_err(|e| e.to_string())?
+        .iter()
+        .map(normalize_key)
+        .collect();
+    Ok(reader
+        .records()
+        .map(|row| {
+            let row = row.ok()?;
+            Some(
+                headers
+                    .iter()
+                    .zip(row.iter())
+                    .map(|(k, v)| (k.clone(), Value::String(v.to_string())))
+                    .collect(),
+            )
+        })
+        .collect())
+}
+
+/// The list of sessions in a JSON export: a top-level array, or an object
+/// holding it under one of `LIST_KEYS` or as its only array.
+fn session_rows(root: Value) -> Result<Vec<Value>, String> {
+    let obj = match root {
+        Value::Array(rows) => return Ok(rows),
+        Value::Object(obj) => obj,
+        _ => return Err("expected a JSON array of sessions".to_string()),
+    };
+    let mut arrays: Vec<(String, Vec<Value>)> = obj
+        .into_iter()
+        .filter_map(|(k, v)| match v {
+            Value::Array(rows) => Some((normalize_key(&k), rows)),
+            _ => None,
+        })
+        .collect();
+    let known = LIST_KEYS
+        .iter()
+        .find_map(|key| arrays.iter().position(|(k, _)| k == key));
+    match known {
+        Some(i) => Ok(arrays.swap_remove(i).1),
+        None if arrays.len() == 1 => Ok(arrays.remove(0).1),
+        None => Err("no list of sessions found in JSON file".to_string()),
+    }
+}
+
+fn read_json(path: &Path) -> Result<Vec<Option<Map<String, Value>>>, String> {
+    let data = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let root: Value = serde_json::from_str(&data).map_err(|e| e.to_string())?;
+    Ok(session_rows(root)?
+        .into_iter()
+        .map(|row| match row {
+            Value::Object(obj) => Some(
+                obj.into_iter()
+                    .map(|(k, v)| (normalize_key(&k), v))
+                    .collect(),
+            ),
+            _ => None,
+        })
+        .collect())
+}
+
+fn pick_path(app: &AppHandle) -> Option<PathBuf> {
+    app.dialog()
+        .file()
+        .set_title("Import History")
+        .add_filter("Session history", &["csv", "json"])
+        .blocking_pick_file()
+        .and_then(|p| p.into_path().ok())
+}
+
+/// Import sessions from another timer's CSV or JSON export. Rows with the
+/// same end time (to the second) and kind as a session already in history
+/// are skipped. Without a
+/// `path` a native open dialog asks for the file; `None` means cancelled.
+pub fn import(
+    app: &AppHandle,
+    path: Option<PathBuf>,
+    format: Option<ImportFormat>,
+) -> Result<Option<ImportReport>, String> {
+    let path = match path.or_else(|| pick_path(app)) {
+        Some(path) => path,
+        None => return Ok(None),
+    };
+    let format = format
+        .or_else(|| ImportFormat::from_path(&path))
+        .ok_or("unknown file type; expected .csv or .json")?;
+    let records = match format {
+        ImportFormat::Csv => read_csv(&path)?,
+        ImportFormat::Json => read_json(&path)?,
+    };
+
+    db::with_conn(|conn| {
+        let mut seen: HashSet<(i64, &str)> = history::query(conn, &SessionFilter::default())?
+            .iter()
+            .map(|s| (s.date.timestamp(), s.kind.as_str()))
+            .collect();
+
+        let mut report = ImportReport::default();
+        let tx = conn.transaction()?;
+        for record in records {
+            let Some(entry) = record.as_ref().and_then(to_session) else {
+                report.malformed += 1;
+                continue;
+            };
+            if !seen.insert((entry.date.timestamp(), entry.kind.as_str())) {
+                report.skipped += 1;
+                continue;
+            }
+            history::insert(&tx, &entry)?;
+            report.imported += 1;
+        }
+        tx.commit()?;
+        Ok(Some(report))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn record(value: Value) -> Map<String, Value> {
+        match value {
+            Value::Object(obj) => obj
+                .into_iter()
+                .map(|(k, v)| (normalize_key(&k), v))
+                .collect(),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn a_date_without_a_time_does_not_hide_the_end_column() {
+        let entry = to_session(&record(json!({
+            "Date": "2026-03-10",
+            "Time": "09:30",
+            "End Time": "2026-03-10T09:55:00Z",
+            "Duration": "25",
+        })))
+        .unwrap();
+        assert_eq!(entry.date.to_rfc3339(), "2026-03-10T09:55:00+00:00");
+        assert_eq!(entry.duration, 25);
+
+        let missing = to_session(&record(json!({ "date": "2026-03-10", "minutes": 25 })));
+        assert!(missing.is_none());
+    }
+
+    #[test]
+    fn a_date_with_a_time_is_the_end() {
+        let entry = to_session(&record(json!({
+            "date": "2026-03-10T10:00:00Z",
+            "mins": 50,
+            "type": "Long Break",
+        })))
+        .unwrap();
+        assert_eq!(entry.date.to_rfc3339(), "2026-03-10T10:00:00+00:00");
+        assert_eq!(entry.kind, SessionKind::LongBreak);
+    }
+
+    #[test]
+    fn sessions_are_found_under_a_known_key_before_any_other_array() {
+        let rows = session_rows(json!({
+            "achievements": [1, 2],
+            "History": [{ "end": "2026-03-10T10:00:00Z" }],
+            "tags": ["a"],
+        }))
+        .unwrap();
+        assert_eq!(rows.len(), 1);
+
+        let rows = session_rows(json!({ "meta": {}, "log": [1, 2, 3] })).unwrap();
+        assert_eq!(rows.len(), 3);
+
+        assert!(session_rows(json!({ "a": [1], "b": [2] })).is_err());
+        assert!(session_rows(json!("sessions")).is_err());
+    }
+}
@@ -0,0 +1,53 @@
+use crate::shortcuts::{self, Action};
+use tauri::AppHandle;
+
+/// Act on launch arguments, both our own and those a second launch forwards
+/// to the running instance. `args` excludes the program name.
+///
+/// Flags: `--start`, `--pause`, `--reset`, `--skip`, `--toggle`,
+/// `--eye-break-now`, `--history`, plus `meow://` links. A second launch with nothing to do expands
+/// the popover so it's clear meow is already running.
+pub fn handle_args(app: &AppHandle, args: &[String], forwarded: bool) {
+    let mut acted = false;
+    for arg in args {
+        acted |= match arg.as_str() {
+            "--start" => {
+                crate::timer::start(app, None);
+                let _ = crate::windows::show_popover(app, true);
+                true
+            }
+            "--pause" => {
+                crate::timer::pause(app);
+                true
+            }
+            "--reset" => {
+                crate::timer::reset(app, None);
+                true
+            }
+            "--skip" => {
+                crate::timer::skip(app);
+                true
+            }
+            "--toggle" => run(app, Action::TogglePopover),
+            "--eye-break-now" => run(app, Action::EyeBreakNow),
+            "--history" => run(app, Action::OpenHistory),
+            // Linux and Windows open links by launching us with the URL.
+            link if link.starts_with("meow:") => {
+                crate::deep_link::open(app, link);
+                true
+            }
+            other => {
+                log::debug!("ignoring launch argument {}", other);
+                false
+            }
+        };
+    }
+    if forwarded && !acted {
+        let _ = crate::windows::show_popover(app, true);
+    }
+}
+
+fn run(app: &AppHandle, action: Action) -> bool {
+    shortcuts::run(app, action);
+    true
+}
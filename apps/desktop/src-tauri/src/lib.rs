@@ -1,16 +1,56 @@
+mod api;
+mod cli;
 mod commands;
+mod control;
+mod db;
+mod deep_link;
+mod dnd;
+mod export;
+mod history;
+mod hooks;
+mod idle;
+mod import;
+mod launch;
+mod lifecycle;
 mod mouse_tracker;
+mod power;
+mod settings;
+mod shortcuts;
+mod stats;
+mod tasks;
+mod timer;
 mod tray;
+mod tray_icon;
+mod webhooks;
 mod windows;
 
-#[cfg(target_os = "macos")]
+#[cfg(any(target_os = "macos", target_os = "linux"))]
 mod platform;
 
+/// Run `meow <command>` as a client of the running app. Returns the exit
+/// code, or `None` if `args` (without the program name) aren't a CLI command.
+pub fn run_cli(args: &[String]) -> Option<i32> {
+    cli::run(args)
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
+        // Must come first: a second launch hands its arguments to the
+        // running instance here and exits before setting anything up.
+        .plugin(tauri_plugin_single_instance::init(|app, argv, _cwd| {
+            launch::handle_args(app, argv.get(1..).unwrap_or_default(), true);
+        }))
+        // Log to stdout and the app's log directory.
+        .plugin(
+            tauri_plugin_log::Builder::new()
+                .level(log::LevelFilter::Info)
+                .build(),
+        )
+        .plugin(tauri_plugin_deep_link::init())
         .plugin(tauri_plugin_global_shortcut::Builder::new().build())
         .plugin(tauri_plugin_notification::init())
+        .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_opener::init())
         .invoke_handler(tauri::generate_handler![
             commands::resize_window,
@@ -18,11 +58,35 @@ pub fn run() {
             commands::update_tray_title,
             commands::window_close,
             commands::focus_window,
-            commands::get_history,
-            commands::save_history,
+            commands::set_pointer_inside,
+            commands::get_tracker_stats,
+            commands::start_timer,
+            commands::pause_timer,
+            commands::resolve_idle,
+            commands::reset_timer,
+            commands::skip_phase,
+            commands::get_timer_state,
+            commands::append_session,
+            commands::list_sessions,
             commands::clear_history,
+            commands::get_stats,
+            commands::export_history,
+            commands::import_history,
+            commands::list_tasks,
+            commands::create_task,
+            commands::rename_task,
+            commands::set_task_estimate,
+            commands::archive_task,
+            commands::get_settings,
+            commands::update_settings,
             commands::open_eye_break,
             commands::close_eye_break,
+            commands::list_monitors,
+            commands::list_pals,
+            commands::set_shortcut,
+            commands::run_action,
+            commands::list_webhook_deliveries,
+            commands::list_hook_runs,
         ])
         .setup(|app| {
             // Accessory policy: no dock icon, but windows can float above full-screen apps.
@@ -30,6 +94,12 @@ pub fn run() {
             #[cfg(target_os = "macos")]
             app.set_activation_policy(tauri::ActivationPolicy::Accessory);
 
+            // Open the SQLite store (sessions, tasks, settings)
+            db::init(app)?;
+
+            // Restore notifications a crashed run left silenced
+            dnd::init();
+
             // Position windows (created by tauri.conf.json)
             windows::setup_windows(app)?;
 
@@ -37,7 +107,7 @@ pub fn run() {
             tray::create_tray(app)?;
 
             // Register global shortcuts
-            commands::register_shortcuts(app)?;
+            shortcuts::init(app.handle());
 
             // Register for NSWorkspaceActiveSpaceDidChangeNotification so the
             // notch window is immediately re-asserted when any app enters or
@@ -45,11 +115,67 @@ pub fn run() {
             #[cfg(target_os = "macos")]
             crate::platform::register_space_observer(app.handle().clone());
 
+            // Re-home the pill and eye break overlays when monitors are
+            // plugged in, unplugged or rescaled
+            #[cfg(any(target_os = "macos", target_os = "linux"))]
+            crate::platform::register_display_observer(app.handle().clone());
+
+            // Drive the focus timer from Rust so it keeps time while the webview is throttled
+            timer::configure(app.handle(), settings::load().timer);
+            timer::start_ticker(app.handle().clone());
+
+            // Act on our own launch flags, e.g. `meow --start`
+            let args: Vec<String> = std::env::args().skip(1).collect();
+            launch::handle_args(app.handle(), &args, false);
+
+            // macOS delivers meow:// links as events rather than arguments
+            #[cfg(target_os = "macos")]
+            {
+                use tauri_plugin_deep_link::DeepLinkExt;
+                let handle = app.handle().clone();
+                app.deep_link().on_open_url(move |event| {
+                    for url in event.urls() {
+                        deep_link::open(&handle, url.as_str());
+                    }
+                });
+            }
+
+            // Builds that weren't installed (AppImage, dev) still need the
+            // scheme registered with the desktop
+            #[cfg(target_os = "linux")]
+            {
+                use tauri_plugin_deep_link::DeepLinkExt;
+                if let Err(e) = app.deep_link().register_all() {
+                    log::warn!("failed to register meow:// links: {}", e);
+                }
+            }
+
+            // Accept commands from the `meow` CLI
+            #[cfg(unix)]
+            if let Err(e) = control::start(app.handle().clone()) {
+                log::warn!("failed to open the control socket: {}", e);
+            }
+
+            // Serve the local HTTP API if it's been switched on
+            api::init(app.handle());
+
+            // Pause focus sessions while the user is away
+            idle::start(app.handle().clone());
+
+            // Keep timers honest across suspend and screen lock
+            power::watch(app.handle());
+
             // Start mouse tracking (auto-collapse when cursor leaves expanded popover)
             mouse_tracker::start(app.handle().clone());
 
             Ok(())
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|_app, event| {
+            // Don't leave other apps' notifications silenced after quitting
+            if let tauri::RunEvent::Exit = event {
+                dnd::shutdown();
+            }
+        });
 }
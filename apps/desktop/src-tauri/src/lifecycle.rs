@@ -0,0 +1,63 @@
+use crate::history::format_date;
+use crate::timer::{Phase, TimerState};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+/// Moments in a session that integrations (webhooks, shell hooks, Do Not
+/// Disturb) react to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LifecycleEvent {
+    /// A focus phase was started from the beginning (not resumed).
+    FocusStart,
+    /// A focus phase ran to the end.
+    FocusComplete,
+    /// A started focus phase was reset or skipped before it ran out.
+    FocusStop,
+    /// A break phase was started from the beginning.
+    BreakStart,
+    EyeBreakStart,
+    EyeBreakEnd,
+}
+
+impl LifecycleEvent {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LifecycleEvent::FocusStart => "focusStart",
+            LifecycleEvent::FocusComplete => "focusComplete",
+            LifecycleEvent::FocusStop => "focusStop",
+            LifecycleEvent::BreakStart => "breakStart",
+            LifecycleEvent::EyeBreakStart => "eyeBreakStart",
+            LifecycleEvent::EyeBreakEnd => "eyeBreakEnd",
+        }
+    }
+}
+
+/// What integrations get to know about an event, as (camelCase name, value)
+/// pairs: webhook template placeholders and shell hook environment variables.
+pub fn fields(event: LifecycleEvent, state: &TimerState) -> [(&'static str, String); 8] {
+    let phase = match state.phase {
+        Phase::Work => "work",
+        Phase::ShortBreak => "shortBreak",
+        Phase::LongBreak => "longBreak",
+    };
+    [
+        ("event", event.as_str().to_string()),
+        ("date", format_date(&Utc::now())),
+        ("phase", phase.to_string()),
+        ("isRunning", state.is_running.to_string()),
+        ("timeLeft", state.time_left.to_string()),
+        ("totalTime", state.total_time.to_string()),
+        ("minutes", (state.total_time / 60).to_string()),
+        ("completedSessions", state.completed_sessions.to_string()),
+    ]
+}
+
+/// Tell every integration about `event`. `state` is the timer state right
+/// after it happened. Must not be called with the timer engine locked.
+pub fn notify(app: &AppHandle, event: LifecycleEvent, state: &TimerState) {
+    crate::webhooks::fire(app, event, state);
+    crate::hooks::run(app, event, state);
+    crate::dnd::on_event(event);
+}
@@ -1,99 +1,315 @@
 use crate::windows;
-use core_graphics::event::CGEvent;
-use core_graphics::event_source::{CGEventSource, CGEventSourceStateID};
-use std::sync::atomic::Ordering;
-use tauri::{AppHandle, Manager};
-
-/// Get cursor position via CoreGraphics (logical points, top-left origin).
-fn cursor_position() -> Option<(f64, f64)> {
-    let source = CGEventSource::new(CGEventSourceStateID::HIDSystemState).ok()?;
-    let event = CGEvent::new(source).ok()?;
-    let pt = event.location();
-    Some((pt.x, pt.y))
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Condvar, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, WindowEvent};
+
+/// Whether the cursor is over the popover webview, as reported by its DOM
+/// enter/leave events. Used where the global cursor position can't be read
+/// (Wayland).
+static POINTER_INSIDE: AtomicBool = AtomicBool::new(false);
+
+/// Set by `wake` so the tracker re-checks right away instead of sleeping out
+/// its current interval.
+static WAKE_PENDING: Mutex<bool> = Mutex::new(false);
+static WAKE_SIGNAL: Condvar = Condvar::new();
+
+/// Popover rect in logical points, cached until the window moves, resizes or
+/// changes scale.
+static GEOMETRY: Mutex<Option<Geometry>> = Mutex::new(None);
+
+/// Tracker wakeups over the last minute.
+static WAKEUPS: Mutex<VecDeque<Instant>> = Mutex::new(VecDeque::new());
+/// Current poll interval in ms; 0 while waiting for events only.
+static POLL_INTERVAL_MS: AtomicU64 = AtomicU64::new(0);
+
+/// While expanded or within `NEAR_DISTANCE` of the pill. The collapse delay
+/// (`OUTSIDE_THRESHOLD` checks) is tuned for this rate.
+const FAST_POLL: Duration = Duration::from_millis(80);
+const MEDIUM_POLL: Duration = Duration::from_millis(250);
+const SLOW_POLL: Duration = Duration::from_millis(1000);
+const NEAR_DISTANCE: f64 = 150.0;
+const MEDIUM_DISTANCE: f64 = 400.0;
+
+/// Slack around the expanded popover before the cursor counts as outside.
+const COLLAPSE_MARGIN: f64 = 8.0;
+
+/// Whether mouse-moved events wake the tracker, so it can sleep while the
+/// cursor is still instead of polling its position.
+const MOVES_WAKE: bool = cfg!(target_os = "macos");
+
+/// Longest the tracker sleeps while waiting for events. On macOS it still
+/// wakes now and then to re-apply the window level while the mouse is still.
+const IDLE_WAIT: Option<Duration> = if cfg!(target_os = "macos") {
+    Some(Duration::from_secs(2))
+} else {
+    None
+};
+
+#[derive(Clone, Copy)]
+struct Geometry {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+    scale: f64,
+}
+
+impl Geometry {
+    fn contains(&self, cx: f64, cy: f64, margin: f64) -> bool {
+        cx >= self.x - margin
+            && cx <= self.x + self.width + margin
+            && cy >= self.y - margin
+            && cy <= self.y + self.height + margin
+    }
+
+    /// Distance from the cursor to the nearest edge, 0 when inside.
+    fn distance(&self, cx: f64, cy: f64) -> f64 {
+        let dx = (self.x - cx).max(cx - (self.x + self.width)).max(0.0);
+        let dy = (self.y - cy).max(cy - (self.y + self.height)).max(0.0);
+        dx.hypot(dy)
+    }
 }
 
-/// Polls cursor position for:
+/// How hard the tracker is working, for checking its battery cost.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrackerStats {
+    pub wakeups_per_minute: usize,
+    /// Current poll interval; `None` while only events wake it.
+    pub poll_interval_ms: Option<u64>,
+}
+
+pub fn stats() -> TrackerStats {
+    let wakeups_per_minute = {
+        let mut wakeups = WAKEUPS.lock().unwrap();
+        prune_wakeups(&mut wakeups, Instant::now());
+        wakeups.len()
+    };
+    let interval = POLL_INTERVAL_MS.load(Ordering::SeqCst);
+    TrackerStats {
+        wakeups_per_minute,
+        poll_interval_ms: (interval > 0).then_some(interval),
+    }
+}
+
+fn prune_wakeups(wakeups: &mut VecDeque<Instant>, now: Instant) {
+    while wakeups
+        .front()
+        .is_some_and(|t| now.duration_since(*t) > Duration::from_secs(60))
+    {
+        wakeups.pop_front();
+    }
+}
+
+fn record_wakeup() {
+    let now = Instant::now();
+    let mut wakeups = WAKEUPS.lock().unwrap();
+    wakeups.push_back(now);
+    prune_wakeups(&mut wakeups, now);
+}
+
+/// Make the tracker re-check immediately.
+fn wake() {
+    *WAKE_PENDING.lock().unwrap() = true;
+    WAKE_SIGNAL.notify_one();
+}
+
+/// Sleep until woken, or until `timeout` passes if one is given.
+fn wait(timeout: Option<Duration>) {
+    POLL_INTERVAL_MS.store(
+        timeout.map_or(0, |t| t.as_millis() as u64),
+        Ordering::SeqCst,
+    );
+    let mut pending = WAKE_PENDING.lock().unwrap();
+    match timeout {
+        Some(timeout) => {
+            pending = WAKE_SIGNAL
+                .wait_timeout_while(pending, timeout, |pending| !*pending)
+                .unwrap()
+                .0;
+        }
+        None => {
+            pending = WAKE_SIGNAL
+                .wait_while(pending, |pending| !*pending)
+                .unwrap();
+        }
+    }
+    *pending = false;
+}
+
+/// Record a webview enter/leave event and react to it right away.
+pub fn set_pointer_inside(inside: bool) {
+    POINTER_INSIDE.store(inside, Ordering::SeqCst);
+    wake();
+}
+
+/// Get the global cursor position in logical points (top-left origin), given
+/// the popover's scale factor.
+fn cursor_position(scale: f64) -> Option<(f64, f64)> {
+    #[cfg(target_os = "macos")]
+    {
+        let _ = scale;
+        crate::platform::cursor_position()
+    }
+    #[cfg(target_os = "linux")]
+    {
+        crate::platform::cursor_position().map(|(x, y)| (x / scale, y / scale))
+    }
+    #[cfg(not(any(target_os = "macos", target_os = "linux")))]
+    {
+        let _ = scale;
+        None
+    }
+}
+
+/// The popover rect, from the cache or freshly queried.
+fn geometry(handle: &AppHandle) -> Option<Geometry> {
+    let mut cached = GEOMETRY.lock().unwrap();
+    if cached.is_none() {
+        let win = handle.get_webview_window("popover")?;
+        let scale = win.scale_factor().ok()?;
+        let pos = win.outer_position().ok()?;
+        let size = win.inner_size().ok()?;
+        *cached = Some(Geometry {
+            x: pos.x as f64 / scale,
+            y: pos.y as f64 / scale,
+            width: size.width as f64 / scale,
+            height: size.height as f64 / scale,
+            scale,
+        });
+    }
+    *cached
+}
+
+/// Watches the cursor for:
 /// - Notch hover: expand popover when cursor enters the notch area
 /// - Auto-collapse: collapse popover when cursor leaves the expanded area
+///
+/// The webview's enter/leave events wake the tracker immediately. On macOS
+/// so does every mouse move, and it only polls to time the collapse and,
+/// every `IDLE_WAIT`, to re-apply the window level. On Linux it polls the
+/// global cursor position between events, backing off the further the
+/// cursor is from the pill. Without a global cursor
+/// position (Wayland) it relies on enter/leave events alone, like macOS.
 pub fn start(handle: AppHandle) {
+    #[cfg(target_os = "macos")]
+    crate::platform::watch_mouse_moves(wake);
+
+    if let Some(win) = handle.get_webview_window("popover") {
+        win.on_window_event(|event| {
+            if matches!(
+                event,
+                WindowEvent::Moved(_)
+                    | WindowEvent::Resized(_)
+                    | WindowEvent::ScaleFactorChanged { .. }
+            ) {
+                *GEOMETRY.lock().unwrap() = None;
+                wake();
+            }
+        });
+    }
+
     std::thread::spawn(move || {
-        let mut tick: u64 = 0;
+        let mut interval = Some(FAST_POLL);
+        #[cfg(target_os = "macos")]
+        let mut last_reapply = Instant::now();
         loop {
-            std::thread::sleep(std::time::Duration::from_millis(80));
-            tick += 1;
+            wait(interval.or(IDLE_WAIT));
+            record_wakeup();
+            let woke = Instant::now();
 
-            // Re-apply window level & behavior every ~500ms on the main thread.
-            // This catches full-screen space transitions quickly — without this,
-            // a newly created full-screen space could leave the window invisible
-            // for up to the full interval.
+            // Re-apply window level & behavior at most every ~500ms on the
+            // main thread. The space observer handles full-screen transitions
+            // as they happen; this is a backstop for anything it misses.
             // (AppKit/NSWindow calls MUST run on the main thread or they silently fail)
-            if tick % 6 == 1 {
+            #[cfg(target_os = "macos")]
+            if last_reapply.elapsed() >= Duration::from_millis(500) {
+                last_reapply = Instant::now();
                 let h = handle.clone();
                 let _ = handle.run_on_main_thread(move || {
                     if let Some(win) = h.get_webview_window("popover") {
-                        #[cfg(target_os = "macos")]
                         crate::platform::set_above_menu_bar(&win);
                     }
                 });
             }
 
-            let (cx, cy) = match cursor_position() {
-                Some(pos) => pos,
-                None => continue,
+            let Some(geo) = geometry(&handle) else {
+                interval = Some(SLOW_POLL);
+                continue;
             };
-
-            let monitor = match handle.primary_monitor() {
-                Ok(Some(m)) => m,
-                _ => continue,
+            interval = match cursor_position(geo.scale) {
+                Some((cx, cy)) => track_cursor(&handle, &geo, cx, cy),
+                None => track_enter_leave(&handle),
             };
-            let scale = monitor.scale_factor();
-            let screen_w = monitor.size().width as f64 / scale;
-            let center_x = screen_w / 2.0;
-            let is_expanded = windows::POPOVER_VISIBLE.load(Ordering::SeqCst);
-
-            // ── Hover to expand: only when cursor is over the actual window (wings) ──
-            if !is_expanded && tick % 2 == 0 {
-                if let Some(win) = handle.get_webview_window("popover") {
-                    if let (Ok(pos), Ok(size)) = (win.outer_position(), win.inner_size()) {
-                        let wx = pos.x as f64 / scale;
-                        let wy = pos.y as f64 / scale;
-                        let ww = size.width as f64 / scale;
-                        let wh = size.height as f64 / scale;
-                        let on_window = cx >= wx && cx <= wx + ww
-                            && cy >= wy && cy <= wy + wh;
-                        if on_window {
-                            let _ = windows::show_popover(&handle, false);
-                            continue;
-                        }
-                    }
-                }
-            }
-
-            // ── Auto-collapse when cursor leaves expanded popover ──
-            if is_expanded && windows::expand_cooldown_elapsed() {
-                let in_popover = if let Some(win) = handle.get_webview_window("popover") {
-                    match (win.outer_position(), win.inner_size()) {
-                        (Ok(pos), Ok(size)) => {
-                            let m = 8.0;
-                            let wx = pos.x as f64 / scale;
-                            let wy = pos.y as f64 / scale;
-                            let ww = size.width as f64 / scale;
-                            let wh = size.height as f64 / scale;
-                            cx >= wx - m && cx <= wx + ww + m
-                                && cy >= wy - m && cy <= wy + wh + m
-                        }
-                        _ => false,
-                    }
-                } else {
-                    false
-                };
-
-                if in_popover {
-                    windows::reset_outside_count();
-                } else if windows::increment_outside() {
-                    let _ = windows::hide_popover(&handle, false);
-                }
+            // A moving mouse sends a stream of events; check at most at the
+            // fast poll rate and let the rest pile up into one wakeup.
+            if MOVES_WAKE && interval.is_none() {
+                std::thread::sleep(FAST_POLL.saturating_sub(woke.elapsed()));
             }
         }
     });
 }
+
+/// Hover-expand and auto-collapse from the global cursor position. Returns
+/// how long to wait before checking again, or `None` to wait for an event.
+fn track_cursor(handle: &AppHandle, geo: &Geometry, cx: f64, cy: f64) -> Option<Duration> {
+    if !windows::POPOVER_VISIBLE.load(Ordering::SeqCst) {
+        // ── Hover to expand: only when cursor is over the actual window (wings) ──
+        if geo.contains(cx, cy, 0.0) {
+            let _ = windows::show_popover(handle, false);
+            return Some(FAST_POLL);
+        }
+        if MOVES_WAKE {
+            return None;
+        }
+        let distance = geo.distance(cx, cy);
+        return Some(if distance < NEAR_DISTANCE {
+            FAST_POLL
+        } else if distance < MEDIUM_DISTANCE {
+            MEDIUM_POLL
+        } else {
+            SLOW_POLL
+        });
+    }
+
+    // ── Auto-collapse when cursor leaves expanded popover ──
+    let inside = geo.contains(cx, cy, COLLAPSE_MARGIN);
+    if windows::expand_cooldown_elapsed() {
+        if inside {
+            windows::reset_outside_count();
+        } else if windows::increment_outside() {
+            let _ = windows::hide_popover(handle, false);
+        }
+    }
+    // Outside, keep checking until the collapse delay runs out even if the
+    // cursor stops moving.
+    if MOVES_WAKE && inside {
+        None
+    } else {
+        Some(FAST_POLL)
+    }
+}
+
+/// Hover-expand and auto-collapse driven by webview enter/leave events alone.
+/// Polls only while a collapse is pending; otherwise waits for the next event.
+fn track_enter_leave(handle: &AppHandle) -> Option<Duration> {
+    let inside = POINTER_INSIDE.load(Ordering::SeqCst);
+    if !windows::POPOVER_VISIBLE.load(Ordering::SeqCst) {
+        if inside {
+            let _ = windows::show_popover(handle, false);
+        }
+        return None;
+    }
+    if inside {
+        windows::reset_outside_count();
+        return None;
+    }
+    if windows::expand_cooldown_elapsed() && windows::increment_outside() {
+        let _ = windows::hide_popover(handle, false);
+        return None;
+    }
+    Some(FAST_POLL)
+}
@@ -0,0 +1,368 @@
+use gtk::gdk;
+use gtk::glib::translate::ToGlibPtr;
+use gtk::prelude::*;
+use std::os::raw::c_int;
+use std::sync::{Once, OnceLock};
+use tauri::{AppHandle, Monitor, WebviewWindow};
+use x11_dl::{xlib, xss};
+use zbus::blocking::Connection;
+
+static HIDING_GUARD_ONCE: Once = Once::new();
+
+// Each thread that asks for the cursor gets its own X connection, since Xlib
+// connections aren't safe to share across threads.
+thread_local! {
+    static X11_POINTER: Option<X11Pointer> = X11Pointer::open();
+    static X11_IDLE: Option<X11Idle> = X11Idle::open();
+}
+
+// Session bus for the idle queries, opened on first use.
+static SESSION_BUS: OnceLock<Option<Connection>> = OnceLock::new();
+
+// gtk-layer-shell is loaded at runtime rather than linked, so the app still
+// starts on systems without it (and on X11, where it's never needed).
+static LAYER_SHELL: OnceLock<Option<LayerShell>> = OnceLock::new();
+
+// Enum values from gtk-layer-shell.h.
+const GTK_LAYER_SHELL_LAYER_OVERLAY: c_int = 3;
+const GTK_LAYER_SHELL_EDGE_TOP: c_int = 2;
+const GTK_LAYER_SHELL_KEYBOARD_MODE_ON_DEMAND: c_int = 2;
+
+type GtkWindowPtr = *mut gtk::ffi::GtkWindow;
+
+struct LayerShell {
+    _lib: libloading::Library,
+    is_supported: unsafe extern "C" fn() -> c_int,
+    is_layer_window: unsafe extern "C" fn(GtkWindowPtr) -> c_int,
+    init_for_window: unsafe extern "C" fn(GtkWindowPtr),
+    set_layer: unsafe extern "C" fn(GtkWindowPtr, c_int),
+    set_anchor: unsafe extern "C" fn(GtkWindowPtr, c_int, c_int),
+    set_exclusive_zone: unsafe extern "C" fn(GtkWindowPtr, c_int),
+    set_keyboard_mode: unsafe extern "C" fn(GtkWindowPtr, c_int),
+    set_monitor: unsafe extern "C" fn(GtkWindowPtr, *mut gdk::ffi::GdkMonitor),
+}
+
+impl LayerShell {
+    fn load() -> Option<Self> {
+        unsafe {
+            let lib = libloading::Library::new("libgtk-layer-shell.so.0").ok()?;
+            Some(Self {
+                is_supported: *lib.get(b"gtk_layer_is_supported\0").ok()?,
+                is_layer_window: *lib.get(b"gtk_layer_is_layer_window\0").ok()?,
+                init_for_window: *lib.get(b"gtk_layer_init_for_window\0").ok()?,
+                set_layer: *lib.get(b"gtk_layer_set_layer\0").ok()?,
+                set_anchor: *lib.get(b"gtk_layer_set_anchor\0").ok()?,
+                set_exclusive_zone: *lib.get(b"gtk_layer_set_exclusive_zone\0").ok()?,
+                set_keyboard_mode: *lib.get(b"gtk_layer_set_keyboard_mode\0").ok()?,
+                set_monitor: *lib.get(b"gtk_layer_set_monitor\0").ok()?,
+                _lib: lib,
+            })
+        }
+    }
+
+    /// The loaded library, if present and the compositor speaks wlr-layer-shell
+    /// (Sway, Hyprland, KDE — but not GNOME).
+    fn get() -> Option<&'static Self> {
+        LAYER_SHELL
+            .get_or_init(Self::load)
+            .as_ref()
+            .filter(|ls| unsafe { (ls.is_supported)() != 0 })
+    }
+}
+
+fn is_wayland() -> bool {
+    gdk::Display::default().is_some_and(|d| d.type_().name() == "GdkWaylandDisplay")
+}
+
+/// Turn the window into an overlay-layer surface anchored to the top edge, so
+/// the compositor keeps it above other windows, centered, on every workspace.
+///
+/// Layer-shell can only be set up before the window first gets a surface, so
+/// windows that want it start hidden and are shown after this. Returns false,
+/// leaving the window alone, if it's too late for that.
+fn apply_layer_shell(ls: &LayerShell, gtk_win: &gtk::ApplicationWindow) -> bool {
+    let ptr: GtkWindowPtr = gtk_win.upcast_ref::<gtk::Window>().to_glib_none().0;
+    unsafe {
+        if (ls.is_layer_window)(ptr) == 0 {
+            if gtk_win.is_realized() {
+                return false;
+            }
+            (ls.init_for_window)(ptr);
+        }
+        (ls.set_layer)(ptr, GTK_LAYER_SHELL_LAYER_OVERLAY);
+        // Anchored to the top edge only, so it's centered horizontally.
+        (ls.set_anchor)(ptr, GTK_LAYER_SHELL_EDGE_TOP, 1);
+        // -1: draw over top panels instead of below them, like the notch pill
+        // sits over the macOS menu bar.
+        (ls.set_exclusive_zone)(ptr, -1);
+        (ls.set_keyboard_mode)(ptr, GTK_LAYER_SHELL_KEYBOARD_MODE_ON_DEMAND);
+    }
+    true
+}
+
+/// Ask the window manager to keep the window above others as a dock on every
+/// workspace (`_NET_WM_STATE_ABOVE`, `_NET_WM_STATE_STICKY`,
+/// `_NET_WM_WINDOW_TYPE_DOCK`). Also the fallback on Wayland compositors
+/// without layer-shell, where these are best-effort hints.
+fn apply_window_hints(gtk_win: &gtk::ApplicationWindow) {
+    if gtk_win.type_hint() != gdk::WindowTypeHint::Dock {
+        // The window type is only read when the window is mapped.
+        let visible = gtk_win.is_visible();
+        if visible {
+            gtk_win.hide();
+        }
+        gtk_win.set_type_hint(gdk::WindowTypeHint::Dock);
+        if visible {
+            gtk_win.show();
+        }
+    }
+    gtk_win.set_keep_above(true);
+    gtk_win.stick();
+    gtk_win.set_skip_taskbar_hint(true);
+    gtk_win.set_skip_pager_hint(true);
+}
+
+/// Keep the window above all others at the top of the screen, on every
+/// workspace. Uses layer-shell on Wayland when the compositor supports it,
+/// and window manager hints otherwise. Safe to call repeatedly and from any
+/// thread; call it before the window is first shown so layer-shell can be
+/// used.
+pub fn set_above_menu_bar(window: &WebviewWindow) {
+    let win = window.clone();
+    let _ = window.run_on_main_thread(move || {
+        let Ok(gtk_win) = win.gtk_window() else {
+            return;
+        };
+        let layered = LayerShell::get()
+            .filter(|_| is_wayland())
+            .is_some_and(|ls| apply_layer_shell(ls, &gtk_win));
+        if !layered {
+            apply_window_hints(&gtk_win);
+        }
+    });
+}
+
+/// Put a layer-shell window on `monitor`. The compositor places layer
+/// surfaces itself and ignores `set_position`, so this is how they end up on
+/// the right output. Does nothing for windows that aren't layer surfaces.
+pub fn set_layer_monitor(window: &WebviewWindow, monitor: &Monitor) {
+    // GDK monitor geometry is in logical pixels.
+    let scale = monitor.scale_factor();
+    let x = (monitor.position().x as f64 / scale).round() as i32;
+    let y = (monitor.position().y as f64 / scale).round() as i32;
+    let win = window.clone();
+    let _ = window.run_on_main_thread(move || {
+        let Some(ls) = LayerShell::get().filter(|_| is_wayland()) else {
+            return;
+        };
+        let Ok(gtk_win) = win.gtk_window() else {
+            return;
+        };
+        let Some(gdk_monitor) = gdk::Display::default().and_then(|d| d.monitor_at_point(x, y))
+        else {
+            return;
+        };
+        let ptr: GtkWindowPtr = gtk_win.upcast_ref::<gtk::Window>().to_glib_none().0;
+        unsafe {
+            if (ls.is_layer_window)(ptr) != 0 {
+                (ls.set_monitor)(ptr, gdk_monitor.to_glib_none().0);
+            }
+        }
+    });
+}
+
+/// Give the window keyboard focus so text inputs work. Window managers may
+/// still refuse focus for dock windows until the user clicks them.
+pub fn activate_app_for_input(window: &WebviewWindow) {
+    let win = window.clone();
+    let _ = window.run_on_main_thread(move || {
+        let Ok(gtk_win) = win.gtk_window() else {
+            return;
+        };
+        gtk_win.present_with_time(gtk::current_event_time());
+        if let Some(gdk_win) = gtk_win.window() {
+            gdk_win.focus(gtk::current_event_time());
+        }
+    });
+}
+
+/// Undo minimizing as soon as it happens, so "show desktop" and
+/// minimize-all shortcuts can't take the pill off screen.
+pub fn prevent_window_hiding(window: &WebviewWindow) {
+    let win = window.clone();
+    let _ = window.run_on_main_thread(move || {
+        let Ok(gtk_win) = win.gtk_window() else {
+            return;
+        };
+        HIDING_GUARD_ONCE.call_once(|| {
+            gtk_win.connect_window_state_event(|w, event| {
+                if event
+                    .new_window_state()
+                    .contains(gdk::WindowState::ICONIFIED)
+                {
+                    w.deiconify();
+                }
+                gtk::glib::Propagation::Proceed
+            });
+        });
+    });
+}
+
+/// Watch GDK for monitors being added, removed, rearranged or rescaled, and
+/// have the window module re-home the pill and overlays. Call on the main
+/// thread.
+pub fn register_display_observer(handle: AppHandle) {
+    let Some(screen) = gdk::Screen::default() else {
+        return;
+    };
+    let h = handle.clone();
+    screen.connect_monitors_changed(move |_| crate::windows::displays_changed(&h));
+    screen.connect_size_changed(move |_| crate::windows::displays_changed(&handle));
+}
+
+/// A private Xlib connection for querying the pointer. libX11 is loaded at
+/// runtime, so this is simply unavailable on Wayland-only systems.
+struct X11Pointer {
+    xlib: xlib::Xlib,
+    display: *mut xlib::Display,
+}
+
+impl X11Pointer {
+    fn open() -> Option<Self> {
+        // Under Wayland, XWayland only reports the pointer while it's over an
+        // X11 window, which would look like a cursor frozen in place.
+        let backend = std::env::var("GDK_BACKEND").unwrap_or_default();
+        if std::env::var_os("WAYLAND_DISPLAY").is_some() && !backend.starts_with("x11") {
+            return None;
+        }
+        let xlib = xlib::Xlib::open().ok()?;
+        let display = unsafe { (xlib.XOpenDisplay)(std::ptr::null()) };
+        if display.is_null() {
+            return None;
+        }
+        Some(Self { xlib, display })
+    }
+
+    fn query(&self) -> Option<(f64, f64)> {
+        let (mut root, mut child) = (0, 0);
+        let (mut root_x, mut root_y, mut win_x, mut win_y) = (0, 0, 0, 0);
+        let mut mask = 0;
+        let found = unsafe {
+            (self.xlib.XQueryPointer)(
+                self.display,
+                (self.xlib.XDefaultRootWindow)(self.display),
+                &mut root,
+                &mut child,
+                &mut root_x,
+                &mut root_y,
+                &mut win_x,
+                &mut win_y,
+                &mut mask,
+            )
+        };
+        (found != 0).then_some((root_x as f64, root_y as f64))
+    }
+}
+
+impl Drop for X11Pointer {
+    fn drop(&mut self) {
+        unsafe { (self.xlib.XCloseDisplay)(self.display) };
+    }
+}
+
+/// Get the cursor position via XQueryPointer, in physical pixels on the root
+/// window (top-left origin). `None` on Wayland or without an X server; the
+/// caller then relies on the webview's enter/leave events instead.
+pub fn cursor_position() -> Option<(f64, f64)> {
+    X11_POINTER.with(|pointer| pointer.as_ref()?.query())
+}
+
+/// The XScreenSaver extension, which reports how long since the last input
+/// event. Like the pointer query, only meaningful on a real X session.
+struct X11Idle {
+    xlib: xlib::Xlib,
+    xss: xss::Xss,
+    display: *mut xlib::Display,
+}
+
+impl X11Idle {
+    fn open() -> Option<Self> {
+        if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+            return None;
+        }
+        let xlib = xlib::Xlib::open().ok()?;
+        let xss = xss::Xss::open().ok()?;
+        let display = unsafe { (xlib.XOpenDisplay)(std::ptr::null()) };
+        if display.is_null() {
+            return None;
+        }
+        let (mut event_base, mut error_base) = (0, 0);
+        let supported =
+            unsafe { (xss.XScreenSaverQueryExtension)(display, &mut event_base, &mut error_base) };
+        if supported == 0 {
+            unsafe { (xlib.XCloseDisplay)(display) };
+            return None;
+        }
+        Some(Self { xlib, xss, display })
+    }
+
+    fn idle_ms(&self) -> Option<u64> {
+        let mut info: xss::XScreenSaverInfo = unsafe { std::mem::zeroed() };
+        let status = unsafe {
+            (self.xss.XScreenSaverQueryInfo)(
+                self.display,
+                (self.xlib.XDefaultRootWindow)(self.display),
+                &mut info,
+            )
+        };
+        (status != 0).then_some(info.idle as u64)
+    }
+}
+
+impl Drop for X11Idle {
+    fn drop(&mut self) {
+        unsafe { (self.xlib.XCloseDisplay)(self.display) };
+    }
+}
+
+/// Mutter's idle monitor (GNOME, including on Wayland), in milliseconds.
+fn mutter_idle_ms(conn: &Connection) -> zbus::Result<u64> {
+    let reply = conn.call_method(
+        Some("org.gnome.Mutter.IdleMonitor"),
+        "/org/gnome/Mutter/IdleMonitor/Core",
+        Some("org.gnome.Mutter.IdleMonitor"),
+        "GetIdletime",
+        &(),
+    )?;
+    reply.body().deserialize()
+}
+
+/// The freedesktop screensaver's idle time (KDE and others), in seconds.
+fn screensaver_idle_secs(conn: &Connection) -> zbus::Result<u32> {
+    let reply = conn.call_method(
+        Some("org.freedesktop.ScreenSaver"),
+        "/org/freedesktop/ScreenSaver",
+        Some("org.freedesktop.ScreenSaver"),
+        "GetSessionIdleTime",
+        &(),
+    )?;
+    reply.body().deserialize()
+}
+
+/// Seconds since the user last touched the keyboard or mouse. Tries Mutter's
+/// idle monitor, then `org.freedesktop.ScreenSaver`, then XScreenSaver.
+/// `None` if none of them is available.
+pub fn idle_seconds() -> Option<u64> {
+    let bus = SESSION_BUS.get_or_init(|| Connection::session().ok());
+    if let Some(conn) = bus {
+        if let Ok(ms) = mutter_idle_ms(conn) {
+            return Some(ms / 1000);
+        }
+        if let Ok(secs) = screensaver_idle_secs(conn) {
+            return Some(secs as u64);
+        }
+    }
+    X11_IDLE
+        .with(|idle| idle.as_ref()?.idle_ms())
+        .map(|ms| ms / 1000)
+}
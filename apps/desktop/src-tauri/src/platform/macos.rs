@@ -6,10 +6,63 @@ use tauri::WebviewWindow;
 static SPACE_HANDLE: Mutex<Option<tauri::AppHandle>> = Mutex::new(None);
 static OBSERVER_ONCE: Once = Once::new();
 
+// App handle for the ObjC screen-parameters callback.
+static DISPLAY_HANDLE: Mutex<Option<tauri::AppHandle>> = Mutex::new(None);
+static DISPLAY_OBSERVER_ONCE: Once = Once::new();
+
+static MOUSE_MONITOR_ONCE: Once = Once::new();
+
 // Stores the ZFNotchWindow subclass pointer (as usize — raw ptrs aren't Send).
 static NOTCH_WIN_CLASS: AtomicUsize = AtomicUsize::new(0);
 static NOTCH_WIN_CLASS_ONCE: Once = Once::new();
 
+/// Get the cursor position via CoreGraphics (logical points, top-left origin).
+pub fn cursor_position() -> Option<(f64, f64)> {
+    use core_graphics::event::CGEvent;
+    use core_graphics::event_source::{CGEventSource, CGEventSourceStateID};
+
+    let source = CGEventSource::new(CGEventSourceStateID::HIDSystemState).ok()?;
+    let event = CGEvent::new(source).ok()?;
+    let pt = event.location();
+    Some((pt.x, pt.y))
+}
+
+/// Call `on_move` whenever the mouse moves, over any app or our own windows,
+/// so the cursor tracker can sleep until there's something to check. Must
+/// be called on the main thread.
+pub fn watch_mouse_moves(on_move: fn()) {
+    use block::ConcreteBlock;
+    use cocoa::appkit::NSEventMask;
+    use cocoa::base::id;
+    use objc::{class, msg_send, sel, sel_impl};
+
+    MOUSE_MONITOR_ONCE.call_once(|| unsafe {
+        let mask = (NSEventMask::NSMouseMovedMask
+            | NSEventMask::NSLeftMouseDraggedMask
+            | NSEventMask::NSRightMouseDraggedMask)
+            .bits();
+        // Global monitors only see other apps' events; the local one covers
+        // ours and must hand each event back.
+        let global = ConcreteBlock::new(move |_event: id| on_move()).copy();
+        let local = ConcreteBlock::new(move |event: id| {
+            on_move();
+            event
+        })
+        .copy();
+        let _: id = msg_send![class!(NSEvent),
+            addGlobalMonitorForEventsMatchingMask: mask
+            handler: &*global
+        ];
+        let _: id = msg_send![class!(NSEvent),
+            addLocalMonitorForEventsMatchingMask: mask
+            handler: &*local
+        ];
+        // The monitors stay installed for the life of the app.
+        std::mem::forget(global);
+        std::mem::forget(local);
+    });
+}
+
 /// Set the alpha value (opacity) of a window on macOS.
 pub fn set_opacity(window: &WebviewWindow, opacity: f64) {
     #[cfg(target_os = "macos")]
@@ -36,6 +89,24 @@ extern "C" {
 #[cfg(target_os = "macos")]
 const K_CG_MAXIMUM_WINDOW_LEVEL_KEY: i32 = 15;
 
+extern "C" {
+    fn CGEventSourceSecondsSinceLastEventType(state_id: i32, event_type: u32) -> f64;
+}
+// kCGEventSourceStateCombinedSessionState and kCGAnyInputEventType.
+const K_CG_COMBINED_SESSION_STATE: i32 = 0;
+const K_CG_ANY_INPUT_EVENT_TYPE: u32 = !0;
+
+/// Seconds since the user last touched the keyboard or mouse.
+pub fn idle_seconds() -> Option<u64> {
+    let secs = unsafe {
+        CGEventSourceSecondsSinceLastEventType(
+            K_CG_COMBINED_SESSION_STATE,
+            K_CG_ANY_INPUT_EVENT_TYPE,
+        )
+    };
+    (secs >= 0.0).then_some(secs as u64)
+}
+
 /// Activate the app so its window can receive keyboard input.
 ///
 /// Accessory-policy apps are never "active" in the normal sense, so
@@ -45,8 +116,9 @@ const K_CG_MAXIMUM_WINDOW_LEVEL_KEY: i32 = 15;
 /// WebView input can accept typing.
 ///
 /// Safe to call frequently — for Accessory-policy apps, activation is
-/// ephemeral and doesn't steal focus from the current foreground app.
-pub fn activate_app_for_input() {
+/// ephemeral and doesn't steal focus from the current foreground app. The
+/// window is unused here; activation is app-wide on macOS.
+pub fn activate_app_for_input(_window: &WebviewWindow) {
     use cocoa::base::{id, YES};
     use objc::{class, msg_send, sel, sel_impl};
     unsafe {
@@ -131,10 +203,10 @@ pub fn register_space_observer(handle: tauri::AppHandle) {
             if let Some(handle) = h {
                 let h2 = handle.clone();
                 let _ = handle.run_on_main_thread(move || {
-                    // Activate the app so orderFrontRegardless works in the
-                    // new Space. Accessory-policy apps don't steal focus.
-                    activate_app_for_input();
                     if let Some(win) = h2.get_webview_window("popover") {
+                        // Activate the app so orderFrontRegardless works in the
+                        // new Space. Accessory-policy apps don't steal focus.
+                        activate_app_for_input(&win);
                         set_above_menu_bar(&win);
                     }
                 });
@@ -163,6 +235,47 @@ pub fn register_space_observer(handle: tauri::AppHandle) {
     });
 }
 
+/// Register for NSApplicationDidChangeScreenParametersNotification, posted
+/// when a display is connected, disconnected, rearranged or changes
+/// resolution, so the window module can re-home the pill and overlays.
+pub fn register_display_observer(handle: tauri::AppHandle) {
+    use cocoa::base::{id, nil};
+    use cocoa::foundation::NSString;
+    use objc::declare::ClassDecl;
+    use objc::runtime::{Object, Sel};
+    use objc::{class, msg_send, sel, sel_impl};
+
+    *DISPLAY_HANDLE.lock().unwrap() = Some(handle);
+
+    DISPLAY_OBSERVER_ONCE.call_once(|| unsafe {
+        extern "C" fn on_screens_change(_this: &Object, _cmd: Sel, _notif: id) {
+            let h = DISPLAY_HANDLE.lock().unwrap().clone();
+            if let Some(handle) = h {
+                crate::windows::displays_changed(&handle);
+            }
+        }
+
+        let mut decl = ClassDecl::new("ZFDisplayObserver", class!(NSObject))
+            .expect("ZFDisplayObserver class declaration failed");
+        decl.add_method(
+            sel!(onScreensChange:),
+            on_screens_change as extern "C" fn(&Object, Sel, id),
+        );
+        let cls = decl.register();
+
+        let observer: id = msg_send![cls, new];
+        let nc: id = msg_send![class!(NSNotificationCenter), defaultCenter];
+        let name =
+            NSString::alloc(nil).init_str("NSApplicationDidChangeScreenParametersNotification");
+        let _: () = msg_send![nc,
+            addObserver: observer
+            selector: sel!(onScreensChange:)
+            name: name
+            object: nil
+        ];
+    });
+}
+
 /// Hide the zoom (green fullscreen) button from a window's title bar.
 pub fn hide_zoom_button(window: &WebviewWindow) {
     #[cfg(target_os = "macos")]
@@ -1,18 +1,48 @@
 #[cfg(target_os = "macos")]
 mod macos;
 
+#[cfg(target_os = "linux")]
+mod linux;
+
 #[cfg(target_os = "macos")]
 pub use macos::set_opacity;
 
+#[cfg(target_os = "linux")]
+pub use linux::cursor_position;
+#[cfg(target_os = "macos")]
+pub use macos::cursor_position;
+
+#[cfg(target_os = "macos")]
+pub use macos::watch_mouse_moves;
+
+#[cfg(target_os = "linux")]
+pub use linux::idle_seconds;
+#[cfg(target_os = "macos")]
+pub use macos::idle_seconds;
+
+#[cfg(target_os = "linux")]
+pub use linux::set_above_menu_bar;
 #[cfg(target_os = "macos")]
 pub use macos::set_above_menu_bar;
 
+#[cfg(target_os = "linux")]
+pub use linux::set_layer_monitor;
+
 #[cfg(target_os = "macos")]
 pub use macos::register_space_observer;
 
+#[cfg(target_os = "linux")]
+pub use linux::register_display_observer;
+#[cfg(target_os = "macos")]
+pub use macos::register_display_observer;
+
+#[cfg(target_os = "linux")]
+pub use linux::activate_app_for_input;
 #[cfg(target_os = "macos")]
 pub use macos::activate_app_for_input;
 
+#[cfg(target_os = "linux")]
+pub use linux::prevent_window_hiding;
 #[cfg(target_os = "macos")]
 pub use macos::prevent_window_hiding;
 
@@ -0,0 +1,104 @@
+use tauri::AppHandle;
+use zbus::blocking::{Connection, Proxy};
+use zbus::zvariant::{OwnedFd, OwnedObjectPath};
+
+const LOGIND: &str = "org.freedesktop.login1";
+const MANAGER_PATH: &str = "/org/freedesktop/login1";
+const MANAGER: &str = "org.freedesktop.login1.Manager";
+const SESSION: &str = "org.freedesktop.login1.Session";
+
+/// Ask logind to hold off suspending until we've seen `PrepareForSleep`.
+/// Without it the system can be asleep before the signal is handled, and
+/// the suspend time recorded late. Closing the returned fd lets it go ahead;
+/// logind stops waiting on its own after `InhibitDelayMaxSec`.
+fn delay_sleep(manager: &Proxy) -> Option<OwnedFd> {
+    let what = (
+        "sleep",
+        "meow",
+        "Noting when the timer was suspended",
+        "delay",
+    );
+    match manager.call("Inhibit", &what) {
+        Ok(fd) => Some(fd),
+        Err(e) => {
+            log::warn!("couldn't delay suspend: {}", e);
+            None
+        }
+    }
+}
+
+/// `PrepareForSleep(true)` before suspending, `(false)` after waking.
+fn watch_sleep(conn: &Connection, app: AppHandle) -> zbus::Result<()> {
+    let manager = Proxy::new(conn, LOGIND, MANAGER_PATH, MANAGER)?;
+    let signals = manager.receive_signal("PrepareForSleep")?;
+    let mut delay = delay_sleep(&manager);
+    std::thread::spawn(move || {
+        for msg in signals {
+            match msg.body().deserialize::<bool>() {
+                Ok(true) => {
+                    super::suspend(&app);
+                    // Noted; let the suspend go ahead.
+                    drop(delay.take());
+                }
+                Ok(false) => {
+                    super::resume(&app);
+                    if delay.is_none() {
+                        delay = delay_sleep(&manager);
+                    }
+                }
+                Err(e) => log::warn!("unexpected PrepareForSleep: {}", e),
+            }
+        }
+    });
+    Ok(())
+}
+
+/// Our login session's Lock/Unlock signals (sent by `loginctl lock-session`
+/// and friends), plus its `LockedHint`, which desktops set when their own
+/// lock screen comes and goes.
+fn watch_lock(conn: &Connection, app: AppHandle) -> zbus::Result<()> {
+    let manager = Proxy::new(conn, LOGIND, MANAGER_PATH, MANAGER)?;
+    let path: OwnedObjectPath = manager.call("GetSession", &("auto",))?;
+    let session = Proxy::new(conn, LOGIND, path.into_inner(), SESSION)?;
+
+    let signals = session.receive_all_signals()?;
+    let h = app.clone();
+    std::thread::spawn(move || {
+        for msg in signals {
+            match msg.header().member().map(|m| m.as_str()) {
+                Some("Lock") => super::lock(&h),
+                Some("Unlock") => super::unlock(&h),
+                _ => {}
+            }
+        }
+    });
+
+    std::thread::spawn(move || {
+        for change in session.receive_property_changed::<bool>("LockedHint") {
+            match change.get() {
+                Ok(true) => super::lock(&app),
+                Ok(false) => super::unlock(&app),
+                Err(e) => log::warn!("unreadable LockedHint: {}", e),
+            }
+        }
+    });
+    Ok(())
+}
+
+/// Subscribe to logind on the system bus. Each watcher runs on its own
+/// thread for the life of the app.
+pub fn watch(app: &AppHandle) {
+    let conn = match Connection::system() {
+        Ok(conn) => conn,
+        Err(e) => {
+            log::warn!("no system bus, not following suspend or lock: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = watch_sleep(&conn, app.clone()) {
+        log::warn!("couldn't follow suspend/resume: {}", e);
+    }
+    if let Err(e) = watch_lock(&conn, app.clone()) {
+        log::warn!("couldn't follow screen locking: {}", e);
+    }
+}
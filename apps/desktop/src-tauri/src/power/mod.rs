@@ -0,0 +1,105 @@
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+use tauri::{AppHandle, Emitter};
+
+#[cfg(target_os = "linux")]
+mod linux;
+
+/// When the system went to sleep, on both clocks: the monotonic clock the
+/// timer runs on stops while suspended, the wall clock doesn't.
+static SUSPENDED: Mutex<Option<(Instant, SystemTime)>> = Mutex::new(None);
+/// When the screen was locked.
+static LOCKED: Mutex<Option<SystemTime>> = Mutex::new(None);
+
+#[derive(Clone, Copy, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+enum Reason {
+    Suspend,
+    Lock,
+}
+
+/// Payload of the "system-sleep" event.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SystemSleep {
+    reason: Reason,
+}
+
+/// Payload of the "system-resume" event.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SystemResume {
+    reason: Reason,
+    /// Wall-clock seconds since the matching "system-sleep".
+    away_seconds: u64,
+    /// Away for at least an eye break, so the eye-break interval starts over.
+    eye_break_reset: bool,
+}
+
+fn went_away(app: &AppHandle, reason: Reason) {
+    let _ = app.emit("system-sleep", SystemSleep { reason });
+}
+
+fn came_back(app: &AppHandle, reason: Reason, since: SystemTime) {
+    let away = SystemTime::now().duration_since(since).unwrap_or_default();
+    let eye_break = crate::settings::load().eye_break;
+    let payload = SystemResume {
+        reason,
+        away_seconds: away.as_secs(),
+        eye_break_reset: away.as_secs() >= eye_break.break_duration_seconds as u64,
+    };
+    let _ = app.emit("system-resume", payload);
+}
+
+/// The system is about to suspend.
+pub fn suspend(app: &AppHandle) {
+    *SUSPENDED.lock().unwrap() = Some((Instant::now(), SystemTime::now()));
+    went_away(app, Reason::Suspend);
+}
+
+/// The system woke up. A running timer is moved on by the time spent
+/// asleep, which its monotonic clock missed.
+pub fn resume(app: &AppHandle) {
+    let Some((instant, wall)) = SUSPENDED.lock().unwrap().take() else {
+        return;
+    };
+    let asleep = SystemTime::now().duration_since(wall).unwrap_or_default();
+    let missed = asleep.saturating_sub(instant.elapsed());
+    if missed > Duration::ZERO {
+        crate::timer::catch_up(app, missed);
+    }
+    came_back(app, Reason::Suspend, wall);
+}
+
+/// The screen was locked. Repeats (e.g. both a signal and a property change)
+/// are ignored.
+pub fn lock(app: &AppHandle) {
+    {
+        let mut locked = LOCKED.lock().unwrap();
+        if locked.is_some() {
+            return;
+        }
+        *locked = Some(SystemTime::now());
+    }
+    went_away(app, Reason::Lock);
+}
+
+/// The screen was unlocked.
+pub fn unlock(app: &AppHandle) {
+    let Some(since) = LOCKED.lock().unwrap().take() else {
+        return;
+    };
+    came_back(app, Reason::Lock, since);
+}
+
+/// Start following suspend/resume and screen locking, where supported.
+/// Only Linux is covered, through logind. Elsewhere, including macOS, no
+/// "system-sleep"/"system-resume" events are sent and a timer running over
+/// a suspend isn't moved on by the time spent asleep.
+pub fn watch(app: &AppHandle) {
+    #[cfg(target_os = "linux")]
+    linux::watch(app);
+    #[cfg(not(target_os = "linux"))]
+    let _ = app;
+}
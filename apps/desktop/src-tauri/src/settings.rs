@@ -0,0 +1,265 @@
+use crate::api::{self, ApiSettings};
+use crate::db;
+use crate::hooks::{self, HookSettings};
+use crate::idle::{self, IdleSettings};
+use crate::shortcuts::{self, Bindings};
+use crate::timer::{Phase, TimerConfig};
+use crate::webhooks::{self, Webhook};
+use crate::windows::PillMonitor;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter};
+
+/// Serializes `update`'s load-merge-save so concurrent patches from
+/// different windows can't undo each other.
+static UPDATE_LOCK: Mutex<()> = Mutex::new(());
+
+/// Bump when a field is renamed or its meaning changes, and teach `upgrade`
+/// how to carry older documents forward. Adding a field with a default
+/// doesn't need a bump.
+pub const SETTINGS_VERSION: u32 = 1;
+
+/// Key of the settings document in the `settings` table.
+const SETTINGS_KEY: &str = "settings";
+
+/// A focus pal, the companion shown in the pill and saved with sessions.
+#[derive(Clone, Copy, Debug, Serialize)]
+pub struct Pal {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub icon: &'static str,
+}
+
+/// The focus pals in the order the popover, tray and overlay offer them.
+/// `Settings::pal` indexes this.
+pub const PALS: [Pal; 5] = [
+    Pal {
+        id: "cat",
+        name: "Luna",
+        icon: "\u{1F431}",
+    },
+    Pal {
+        id: "fox",
+        name: "Rusty",
+        icon: "\u{1F98A}",
+    },
+    Pal {
+        id: "owl",
+        name: "Hoot",
+        icon: "\u{1F989}",
+    },
+    Pal {
+        id: "panda",
+        name: "Bamboo",
+        icon: "\u{1F43C}",
+    },
+    Pal {
+        id: "bunny",
+        name: "Clover",
+        icon: "\u{1F430}",
+    },
+];
+
+/// Every user preference, shared by the popover, the eye-break overlay and
+/// the Rust side (tray, shortcuts). Missing fields fall back to defaults.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Settings {
+    pub version: u32,
+    pub timer: TimerConfig,
+    /// Index of the selected focus pal.
+    pub pal: usize,
+    pub eye_break: EyeBreakSettings,
+    pub pill_monitor: PillMonitor,
+    /// Global shortcut per action.
+    pub shortcuts: Bindings,
+    pub api: ApiSettings,
+    pub webhooks: Vec<Webhook>,
+    pub hooks: HookSettings,
+    /// Silence other apps' notifications during focus sessions.
+    pub focus_dnd: bool,
+    pub idle: IdleSettings,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            version: SETTINGS_VERSION,
+            timer: TimerConfig::default(),
+            pal: 0,
+            eye_break: EyeBreakSettings::default(),
+            pill_monitor: PillMonitor::default(),
+            shortcuts: shortcuts::default_bindings(),
+            api: ApiSettings::default(),
+            webhooks: Vec::new(),
+            hooks: HookSettings::default(),
+            focus_dnd: false,
+            idle: IdleSettings::default(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct EyeBreakSettings {
+    pub enabled: bool,
+    /// 20-20-20 rule: every 20 minutes...
+    pub interval_minutes: u32,
+    /// ...look away for 20 seconds.
+    pub break_duration_seconds: u32,
+    /// When true, the user cannot skip or snooze the break.
+    pub strict_mode: bool,
+}
+
+impl Default for EyeBreakSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_minutes: 20,
+            break_duration_seconds: 20,
+            strict_mode: false,
+        }
+    }
+}
+
+impl Settings {
+    fn validate(&self) -> Result<(), String> {
+        let t = &self.timer;
+        for (name, minutes) in [
+            ("minutes", t.minutes),
+            ("workMinutes", t.work_minutes),
+            ("shortBreakMinutes", t.short_break_minutes),
+            ("longBreakMinutes", t.long_break_minutes),
+        ] {
+            if !(1..=240).contains(&minutes) {
+                return Err(format!("timer.{} must be between 1 and 240", name));
+            }
+        }
+        if !(1..=12).contains(&t.long_break_interval) {
+            return Err("timer.longBreakInterval must be between 1 and 12".to_string());
+        }
+        if self.pal >= PALS.len() {
+            return Err(format!("pal must be below {}", PALS.len()));
+        }
+        let e = &self.eye_break;
+        if !(1..=240).contains(&e.interval_minutes) {
+            return Err("eyeBreak.intervalMinutes must be between 1 and 240".to_string());
+        }
+        if !(5..=600).contains(&e.break_duration_seconds) {
+            return Err("eyeBreak.breakDurationSeconds must be between 5 and 600".to_string());
+        }
+        if self.api.port < 1024 {
+            return Err("api.port must be 1024 or above".to_string());
+        }
+        webhooks::validate(&self.webhooks)?;
+        hooks::validate(&self.hooks)?;
+        idle::validate(&self.idle)?;
+        shortcuts::validate(&self.shortcuts)
+    }
+}
+
+/// Carry a stored document forward to `SETTINGS_VERSION`.
+fn upgrade(mut raw: Value) -> Value {
+    let version = raw.get("version").and_then(Value::as_u64).unwrap_or(0);
+    if version < 1 {
+        // Unversioned documents already have the v1 shape.
+        raw["version"] = SETTINGS_VERSION.into();
+    }
+    raw
+}
+
+/// Recursively merge `patch` into `target`. Objects are merged key by key;
+/// any other value replaces what was there.
+fn merge(target: &mut Value, patch: Value) {
+    match (target, patch) {
+        (Value::Object(target), Value::Object(patch)) => {
+            for (key, value) in patch {
+                merge(target.entry(key).or_insert(Value::Null), value);
+            }
+        }
+        (target, patch) => *target = patch,
+    }
+}
+
+/// Load settings, falling back to defaults for anything missing or unreadable.
+pub fn load() -> Settings {
+    let raw = db::with_conn(|conn| db::get_setting(conn, SETTINGS_KEY));
+    match raw {
+        Ok(Some(raw)) => serde_json::from_str(&raw)
+            .map(upgrade)
+            .and_then(serde_json::from_value)
+            .unwrap_or_else(|e| {
+                log::warn!("unreadable settings, using defaults: {}", e);
+                Settings::default()
+            }),
+        Ok(None) => Settings::default(),
+        Err(e) => {
+            log::warn!("failed to read settings, using defaults: {}", e);
+            Settings::default()
+        }
+    }
+}
+
+fn save(settings: &Settings) -> Result<(), String> {
+    let raw = serde_json::to_string(settings).map_err(|e| e.to_string())?;
+    db::with_conn(|conn| db::set_setting(conn, SETTINGS_KEY, &raw))
+}
+
+/// Merge a partial settings object into the stored settings, persist the
+/// result and broadcast it to every window as "settings-changed".
+pub fn update(app: &AppHandle, patch: Value) -> Result<Settings, String> {
+    let _guard = UPDATE_LOCK.lock().unwrap();
+    let previous = load();
+    let mut raw = serde_json::to_value(&previous).map_err(|e| e.to_string())?;
+    merge(&mut raw, patch);
+    let mut settings: Settings = serde_json::from_value(raw).map_err(|e| e.to_string())?;
+    settings.version = SETTINGS_VERSION;
+    settings.validate()?;
+    if settings.api.enabled && settings.api.token.is_empty() {
+        settings.api.token = api::generate_token()?;
+    }
+    // Registering can still fail if another app owns a shortcut, so do it
+    // before anything is saved.
+    let shortcuts_changed = settings.shortcuts != previous.shortcuts;
+    if shortcuts_changed {
+        shortcuts::apply(app, &settings.shortcuts, &previous.shortcuts)?;
+    }
+    // Put back what was already applied when a later step fails, so the
+    // live shortcuts and API keep matching the stored settings.
+    let roll_back = |api_applied: bool| {
+        if api_applied {
+            if let Err(e) = api::apply(app, &previous.api, &settings.api) {
+                log::warn!("failed to restore the previous HTTP API: {}", e);
+            }
+        }
+        if shortcuts_changed {
+            if let Err(e) = shortcuts::apply(app, &previous.shortcuts, &settings.shortcuts) {
+                log::warn!("failed to restore previous shortcuts: {}", e);
+            }
+        }
+    };
+    // Likewise the API's port may be taken.
+    let api_changed = settings.api != previous.api;
+    if api_changed {
+        api::apply(app, &settings.api, &previous.api).inspect_err(|_| roll_back(false))?;
+    }
+    save(&settings).inspect_err(|_| roll_back(api_changed))?;
+
+    if settings.timer != previous.timer {
+        crate::timer::configure(app, settings.timer.clone());
+    }
+    if settings.pill_monitor != previous.pill_monitor {
+        if let Err(e) = crate::windows::place_popover(app) {
+            log::warn!("failed to move the pill: {}", e);
+        }
+    }
+    if settings.focus_dnd != previous.focus_dnd {
+        // Takes effect on a focus session that's already under way.
+        let state = crate::timer::state();
+        crate::dnd::set(settings.focus_dnd && state.is_running && state.phase == Phase::Work);
+    }
+    crate::tray::refresh(app);
+    let _ = app.emit("settings-changed", &settings);
+    Ok(settings)
+}
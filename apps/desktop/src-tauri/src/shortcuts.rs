@@ -0,0 +1,165 @@
+use crate::settings::Settings;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+/// Everything a global shortcut can be bound to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Action {
+    TogglePopover,
+    /// Pause a running timer, or start/resume a stopped one.
+    StartPause,
+    Reset,
+    SkipPhase,
+    EyeBreakNow,
+    /// Pause or resume the ambient sounds.
+    MuteSounds,
+    OpenHistory,
+}
+
+impl Action {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Action::TogglePopover => "togglePopover",
+            Action::StartPause => "startPause",
+            Action::Reset => "reset",
+            Action::SkipPhase => "skipPhase",
+            Action::EyeBreakNow => "eyeBreakNow",
+            Action::MuteSounds => "muteSounds",
+            Action::OpenHistory => "openHistory",
+        }
+    }
+}
+
+/// Accelerator per action, e.g. "Super+Shift+F". An action that's missing or
+/// bound to "" has no shortcut.
+pub type Bindings = BTreeMap<Action, String>;
+
+fn bound(bindings: &Bindings) -> impl Iterator<Item = (Action, &str)> {
+    bindings
+        .iter()
+        .filter(|(_, accelerator)| !accelerator.is_empty())
+        .map(|(&action, accelerator)| (action, accelerator.as_str()))
+}
+
+pub fn default_bindings() -> Bindings {
+    BTreeMap::from([(Action::TogglePopover, "Super+Shift+F".to_string())])
+}
+
+fn parse(accelerator: &str) -> Result<Shortcut, String> {
+    Shortcut::from_str(accelerator)
+        .map_err(|e| format!("invalid shortcut \"{}\": {}", accelerator, e))
+}
+
+/// Check every accelerator parses and no two actions share one.
+pub fn validate(bindings: &Bindings) -> Result<(), String> {
+    let mut seen: Vec<(Shortcut, Action)> = Vec::new();
+    for (action, accelerator) in bound(bindings) {
+        let shortcut = parse(accelerator)?;
+        if let Some((_, other)) = seen.iter().find(|(s, _)| *s == shortcut) {
+            return Err(format!(
+                "{} is already bound to {}",
+                accelerator,
+                other.as_str()
+            ));
+        }
+        seen.push((shortcut, action));
+    }
+    Ok(())
+}
+
+/// Perform an action, whether from a shortcut or the command palette.
+pub fn run(app: &AppHandle, action: Action) {
+    match action {
+        Action::TogglePopover => {
+            let _ = crate::windows::toggle_popover(app);
+        }
+        Action::StartPause => {
+            if crate::timer::state().is_running {
+                crate::timer::pause(app);
+            } else {
+                crate::timer::start(app, None);
+            }
+        }
+        Action::Reset => {
+            crate::timer::reset(app, None);
+        }
+        Action::SkipPhase => {
+            crate::timer::skip(app);
+        }
+        // The eye break and ambient sounds are driven by the popover.
+        Action::EyeBreakNow => {
+            if let Some(w) = app.get_webview_window("popover") {
+                let _ = w.emit("tray-eye-break-now", ());
+            }
+        }
+        Action::MuteSounds => {
+            if let Some(w) = app.get_webview_window("popover") {
+                let _ = w.emit("toggle-mute", ());
+            }
+        }
+        Action::OpenHistory => crate::windows::open_history(app),
+    }
+}
+
+fn register(app: &AppHandle, action: Action, accelerator: &str) -> Result<(), String> {
+    app.global_shortcut()
+        .on_shortcut(parse(accelerator)?, move |app, _shortcut, event| {
+            if event.state() == ShortcutState::Pressed {
+                run(app, action);
+            }
+        })
+        .map_err(|e| format!("couldn't register {}: {}", accelerator, e))
+}
+
+fn register_all(app: &AppHandle, bindings: &Bindings) -> Result<(), String> {
+    app.global_shortcut()
+        .unregister_all()
+        .map_err(|e| e.to_string())?;
+    for (action, accelerator) in bound(bindings) {
+        register(app, action, accelerator)?;
+    }
+    Ok(())
+}
+
+/// Swap the registered shortcuts from `previous` to `bindings` without a
+/// restart. If any can't be registered (e.g. another app owns it), the
+/// previous bindings are restored and the error returned.
+pub fn apply(app: &AppHandle, bindings: &Bindings, previous: &Bindings) -> Result<(), String> {
+    register_all(app, bindings).inspect_err(|_| {
+        if let Err(e) = register_all(app, previous) {
+            log::warn!("failed to restore previous shortcuts: {}", e);
+        }
+    })
+}
+
+/// Register the stored shortcuts at startup. A binding that can't be
+/// registered is logged and skipped rather than failing setup.
+pub fn init(app: &AppHandle) {
+    let bindings = crate::settings::load().shortcuts;
+    for (action, accelerator) in bound(&bindings) {
+        if let Err(e) = register(app, action, accelerator) {
+            log::warn!("{} shortcut: {}", action.as_str(), e);
+        }
+    }
+}
+
+/// Bind `action` to `accelerator`, or unbind it with `None`, and re-register
+/// shortcuts immediately. Fails if another action already uses the shortcut.
+pub fn set(
+    app: &AppHandle,
+    action: Action,
+    accelerator: Option<String>,
+) -> Result<Settings, String> {
+    let accelerator = accelerator
+        .map(|a| a.trim().to_string())
+        .unwrap_or_default();
+    crate::settings::update(
+        app,
+        json!({ "shortcuts": { action.as_str(): accelerator } }),
+    )
+}
@@ -0,0 +1,237 @@
+use crate::db;
+use crate::history::{self, DateRange, SessionEntry, SessionFilter};
+use chrono::{Datelike, Duration, Local, NaiveDate, Timelike};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+
+/// How `Stats::groups` buckets focus time. Calendar buckets use local time.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum GroupBy {
+    #[default]
+    Day,
+    /// ISO weeks, keyed like "2026-W07".
+    Week,
+    Month,
+    Task,
+    Pal,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatsGroup {
+    pub key: String,
+    pub focus_minutes: u64,
+    pub sessions: u32,
+}
+
+/// Aggregates over the focus sessions in a range. Breaks are ignored.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Stats {
+    pub total_focus_minutes: u64,
+    pub focus_sessions: u32,
+    pub completed_sessions: u32,
+    /// Completed sessions as a fraction (0-1) of all focus sessions.
+    pub completion_rate: f64,
+    /// Consecutive days, ending today or yesterday, with a completed focus
+    /// session. Streaks always look at the full history, not just the range.
+    pub current_streak: u32,
+    pub longest_streak: u32,
+    /// Local hour (0-23) with the most focus minutes.
+    pub best_hour: Option<u32>,
+    /// Focus minutes per local hour of day, index 0 = midnight.
+    pub minutes_by_hour: Vec<u64>,
+    /// Focus time per `group_by` bucket. Calendar buckets are sorted
+    /// chronologically; task and pal buckets by most minutes first.
+    pub groups: Vec<StatsGroup>,
+}
+
+fn group_key(entry: &SessionEntry, group_by: GroupBy) -> String {
+    let local = entry.date.with_timezone(&Local);
+    match group_by {
+        GroupBy::Day => local.format("%Y-%m-%d").to_string(),
+        GroupBy::Week => {
+            let week = local.iso_week();
+            format!("{}-W{:02}", week.year(), week.week())
+        }
+        GroupBy::Month => local.format("%Y-%m").to_string(),
+        GroupBy::Task => entry.task.clone(),
+        GroupBy::Pal => entry.pal.clone(),
+    }
+}
+
+fn groups(sessions: &[SessionEntry], group_by: GroupBy) -> Vec<StatsGroup> {
+    let mut buckets: HashMap<String, StatsGroup> = HashMap::new();
+    for s in sessions {
+        let key = group_key(s, group_by);
+        let group = buckets.entry(key.clone()).or_insert(StatsGroup {
+            key,
+            focus_minutes: 0,
+            sessions: 0,
+        });
+        group.focus_minutes += s.duration as u64;
+        group.sessions += 1;
+    }
+
+    let mut groups: Vec<StatsGroup> = buckets.into_values().collect();
+    match group_by {
+        GroupBy::Day | GroupBy::Week | GroupBy::Month => groups.sort_by(|a, b| a.key.cmp(&b.key)),
+        GroupBy::Task | GroupBy::Pal => groups.sort_by(|a, b| {
+            b.focus_minutes
+                .cmp(&a.focus_minutes)
+                .then_with(|| a.key.cmp(&b.key))
+        }),
+    }
+    groups
+}
+
+/// (current, longest) run of consecutive local days with a completed session.
+fn streaks(days: &BTreeSet<NaiveDate>, today: NaiveDate) -> (u32, u32) {
+    let mut longest = 0;
+    let mut run = 0;
+    let mut prev: Option<NaiveDate> = None;
+    for &day in days {
+        run = match prev {
+            Some(p) if p.succ_opt() == Some(day) => run + 1,
+            _ => 1,
+        };
+        longest = longest.max(run);
+        prev = Some(day);
+    }
+
+    // The current streak survives until a full day is missed.
+    let mut current = 0;
+    let mut day = if days.contains(&today) {
+        Some(today)
+    } else {
+        today.pred_opt()
+    };
+    while let Some(d) = day.filter(|d| days.contains(d)) {
+        current += 1;
+        day = d.pred_opt();
+    }
+    (current, longest)
+}
+
+/// Focus minutes per local hour, spreading each session over the hours it
+/// ran in. Sessions are stored by end time, so they started `duration`
+/// minutes earlier.
+fn minutes_by_hour(sessions: &[SessionEntry]) -> Vec<u64> {
+    let mut minutes = vec![0u64; 24];
+    for s in sessions {
+        let start = (s.date - Duration::minutes(s.duration as i64)).with_timezone(&Local);
+        let mut hour = start.hour() as usize;
+        let mut minute = start.minute();
+        let mut left = s.duration;
+        while left > 0 {
+            let take = left.min(60 - minute);
+            minutes[hour] += take as u64;
+            left -= take;
+            hour = (hour + 1) % 24;
+            minute = 0;
+        }
+    }
+    minutes
+}
+
+/// Compute stats from the focus sessions in range and the local days that
+/// have a completed focus session.
+fn compute(focus: &[SessionEntry], active_days: &BTreeSet<NaiveDate>, group_by: GroupBy) -> Stats {
+    let total_focus_minutes = focus.iter().map(|s| s.duration as u64).sum();
+    let focus_sessions = focus.len() as u32;
+    let completed_sessions = focus.iter().filter(|s| s.completed).count() as u32;
+    let completion_rate = if focus_sessions > 0 {
+        completed_sessions as f64 / focus_sessions as f64
+    } else {
+        0.0
+    };
+
+    let minutes_by_hour = minutes_by_hour(focus);
+    let best_hour = minutes_by_hour
+        .iter()
+        .enumerate()
+        .filter(|(_, &m)| m > 0)
+        .max_by_key(|(_, &m)| m)
+        .map(|(h, _)| h as u32);
+
+    let (current_streak, longest_streak) = streaks(active_days, Local::now().date_naive());
+
+    Stats {
+        total_focus_minutes,
+        focus_sessions,
+        completed_sessions,
+        completion_rate,
+        current_streak,
+        longest_streak,
+        best_hour,
+        minutes_by_hour,
+        groups: groups(focus, group_by),
+    }
+}
+
+/// Local days with at least one completed focus session, across all history.
+fn active_days(conn: &Connection) -> rusqlite::Result<BTreeSet<NaiveDate>> {
+    let mut stmt = conn.prepare(
+        "SELECT DISTINCT date(date, 'localtime') FROM sessions
+         WHERE completed = 1 AND kind IN ('focus', 'work')",
+    )?;
+    let days = stmt.query_map([], |row| row.get::<_, String>(0))?;
+    let mut out = BTreeSet::new();
+    for day in days {
+        if let Ok(day) = NaiveDate::parse_from_str(&day?, "%Y-%m-%d") {
+            out.insert(day);
+        }
+    }
+    Ok(out)
+}
+
+/// Aggregate focus time over the session store.
+pub fn get(range: &DateRange, group_by: GroupBy) -> Result<Stats, String> {
+    let filter = SessionFilter {
+        focus_only: true,
+        ..range.filter()
+    };
+    let (focus, days) =
+        db::with_conn(|conn| Ok((history::query(conn, &filter)?, active_days(conn)?)))?;
+    Ok(compute(&focus, &days, group_by))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn focus_ending(hour: u32, minute: u32, duration: u32) -> SessionEntry {
+        let date = Local
+            .with_ymd_and_hms(2026, 3, 10, hour, minute, 0)
+            .unwrap()
+            .with_timezone(&Utc);
+        serde_json::from_value(serde_json::json!({
+            "type": "focus",
+            "duration": duration,
+            "date": date,
+            "completed": true,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn minutes_are_split_across_the_hours_a_session_ran_in() {
+        let by_hour = minutes_by_hour(&[focus_ending(10, 10, 25), focus_ending(14, 0, 90)]);
+        assert_eq!(by_hour[9], 15);
+        assert_eq!(by_hour[10], 10);
+        assert_eq!(by_hour[12], 30);
+        assert_eq!(by_hour[13], 60);
+        assert_eq!(by_hour[14], 0);
+        assert_eq!(by_hour.iter().sum::<u64>(), 115);
+    }
+
+    #[test]
+    fn sessions_before_midnight_wrap_to_late_hours() {
+        let by_hour = minutes_by_hour(&[focus_ending(0, 20, 50)]);
+        assert_eq!(by_hour[23], 30);
+        assert_eq!(by_hour[0], 20);
+    }
+}
@@ -0,0 +1,281 @@
+use crate::db;
+use crate::history::{format_date, MAX_TASK_LEN};
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+const MAX_ESTIMATE: u32 = 100;
+
+/// An entry in the task list, with how much focus it has had so far.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Task {
+    pub id: i64,
+    pub name: String,
+    /// Pomodoros the task is expected to take, if estimated.
+    pub estimate: Option<u32>,
+    pub archived: bool,
+    pub created_at: String,
+    /// Focus sessions run to the end while working on it.
+    pub pomodoros: u32,
+    /// Minutes of those sessions.
+    pub focus_minutes: u32,
+}
+
+/// Column list matching `from_row`, over `tasks` joined to its sessions.
+const TASK_QUERY: &str = "
+    SELECT t.id, t.name, t.estimate, t.archived, t.created_at,
+           COUNT(s.id), COALESCE(SUM(s.duration), 0)
+    FROM tasks t
+    LEFT JOIN sessions s
+        ON s.task_id = t.id AND s.completed = 1 AND s.kind IN ('focus', 'work')";
+
+fn from_row(row: &Row) -> rusqlite::Result<Task> {
+    Ok(Task {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        estimate: row.get(2)?,
+        archived: row.get(3)?,
+        created_at: row.get(4)?,
+        pomodoros: row.get(5)?,
+        focus_minutes: row.get(6)?,
+    })
+}
+
+fn validate_name(name: &str) -> Result<&str, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("task name can't be empty".to_string());
+    }
+    if name.chars().count() > MAX_TASK_LEN {
+        return Err(format!(
+            "task name must be at most {} characters",
+            MAX_TASK_LEN
+        ));
+    }
+    Ok(name)
+}
+
+fn validate_estimate(estimate: Option<u32>) -> Result<(), String> {
+    if estimate.is_some_and(|e| !(1..=MAX_ESTIMATE).contains(&e)) {
+        return Err(format!(
+            "estimate must be between 1 and {} pomodoros",
+            MAX_ESTIMATE
+        ));
+    }
+    Ok(())
+}
+
+/// Whether a task other than `except` is already called `name`, ignoring
+/// case.
+fn name_taken(conn: &Connection, name: &str, except: Option<i64>) -> rusqlite::Result<bool> {
+    conn.query_row(
+        "SELECT 1 FROM tasks WHERE name = ?1 COLLATE NOCASE AND id IS NOT ?2",
+        params![name, except],
+        |_| Ok(()),
+    )
+    .optional()
+    .map(|found| found.is_some())
+}
+
+fn load(conn: &Connection, id: i64) -> rusqlite::Result<Task> {
+    conn.query_row(
+        &format!("{} WHERE t.id = ?1 GROUP BY t.id", TASK_QUERY),
+        [id],
+        from_row,
+    )
+}
+
+fn query(conn: &Connection, include_archived: bool) -> rusqlite::Result<Vec<Task>> {
+    let mut stmt = conn.prepare(&format!(
+        "{} WHERE ?1 OR t.archived = 0 GROUP BY t.id ORDER BY t.created_at DESC, t.id DESC",
+        TASK_QUERY
+    ))?;
+    let rows = stmt.query_map([include_archived], from_row)?;
+    rows.collect()
+}
+
+/// Add a task, or `None` if the name is taken.
+fn insert(conn: &Connection, name: &str, estimate: Option<u32>) -> rusqlite::Result<Option<Task>> {
+    if name_taken(conn, name, None)? {
+        return Ok(None);
+    }
+    conn.execute(
+        "INSERT INTO tasks (name, created_at, estimate) VALUES (?1, ?2, ?3)",
+        params![name, format_date(&Utc::now()), estimate],
+    )?;
+    load(conn, conn.last_insert_rowid()).map(Some)
+}
+
+/// Rename a task and its sessions, or `None` if the name is taken.
+fn update_name(conn: &mut Connection, id: i64, name: &str) -> rusqlite::Result<Option<Task>> {
+    if name_taken(conn, name, Some(id))? {
+        return Ok(None);
+    }
+    let tx = conn.transaction()?;
+    tx.execute(
+        "UPDATE tasks SET name = ?1 WHERE id = ?2",
+        params![name, id],
+    )?;
+    tx.execute(
+        "UPDATE sessions SET task = ?1 WHERE task_id = ?2",
+        params![name, id],
+    )?;
+    tx.commit()?;
+    load(conn, id).map(Some)
+}
+
+/// Tell every window the task list (or its session counts) changed, as
+/// "tasks-changed", and refresh the tray's recent tasks.
+pub fn changed(app: &AppHandle) {
+    let _ = app.emit("tasks-changed", ());
+    crate::tray::refresh(app);
+}
+
+/// Look a task up, failing with a readable message if it doesn't exist.
+pub fn get(id: i64) -> Result<Task, String> {
+    db::with_conn(|conn| load(conn, id).optional())?.ok_or_else(|| format!("no task {}", id))
+}
+
+/// Tasks, newest first; archived ones only if asked for.
+pub fn list(include_archived: bool) -> Result<Vec<Task>, String> {
+    db::with_conn(|conn| query(conn, include_archived))
+}
+
+/// Add a task to the list.
+pub fn create(name: &str, estimate: Option<u32>) -> Result<Task, String> {
+    let name = validate_name(name)?;
+    validate_estimate(estimate)?;
+    db::with_conn(|conn| insert(conn, name, estimate))?
+        .ok_or_else(|| format!("there's already a task called \"{}\"", name))
+}
+
+/// Rename a task. Its past sessions take the new name too.
+pub fn rename(id: i64, name: &str) -> Result<Task, String> {
+    let name = validate_name(name)?;
+    get(id)?;
+    db::with_conn(|conn| update_name(conn, id, name))?
+        .ok_or_else(|| format!("there's already a task called \"{}\"", name))
+}
+
+/// Set or clear a task's estimate.
+pub fn set_estimate(id: i64, estimate: Option<u32>) -> Result<Task, String> {
+    validate_estimate(estimate)?;
+    get(id)?;
+    db::with_conn(|conn| {
+        conn.execute(
+            "UPDATE tasks SET estimate = ?1 WHERE id = ?2",
+            params![estimate, id],
+        )?;
+        load(conn, id)
+    })
+}
+
+/// Archive a task, hiding it from the list without losing its history, or
+/// bring it back.
+pub fn set_archived(id: i64, archived: bool) -> Result<Task, String> {
+    get(id)?;
+    db::with_conn(|conn| {
+        conn.execute(
+            "UPDATE tasks SET archived = ?1 WHERE id = ?2",
+            params![archived, id],
+        )?;
+        load(conn, id)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::{SessionEntry, SessionKind};
+
+    fn open() -> Connection {
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.pragma_update(None, "foreign_keys", true).unwrap();
+        db::migrate(&mut conn).unwrap();
+        conn
+    }
+
+    fn focus_on(conn: &Connection, task_id: i64, minutes: u32) {
+        let entry = SessionEntry {
+            id: None,
+            kind: SessionKind::Focus,
+            duration: minutes,
+            task: String::new(),
+            task_id: Some(task_id),
+            pal: String::new(),
+            date: Utc::now(),
+            completed: true,
+            idle_seconds: 0,
+        };
+        crate::history::insert(conn, &entry).unwrap();
+    }
+
+    fn session_tasks(conn: &Connection) -> Vec<String> {
+        let mut stmt = conn
+            .prepare("SELECT task FROM sessions ORDER BY id")
+            .unwrap();
+        let rows = stmt.query_map([], |row| row.get(0)).unwrap();
+        rows.collect::<rusqlite::Result<_>>().unwrap()
+    }
+
+    #[test]
+    fn names_are_unique_ignoring_case() {
+        let mut conn = open();
+        let report = insert(&conn, "Write report", Some(3)).unwrap().unwrap();
+        assert_eq!(report.estimate, Some(3));
+        assert!(insert(&conn, "write REPORT", None).unwrap().is_none());
+
+        let email = insert(&conn, "Email", None).unwrap().unwrap();
+        assert!(update_name(&mut conn, email.id, "WRITE report")
+            .unwrap()
+            .is_none());
+        // A task can change the case of its own name.
+        let renamed = update_name(&mut conn, report.id, "Write Report").unwrap();
+        assert_eq!(renamed.unwrap().name, "Write Report");
+    }
+
+    #[test]
+    fn estimates_must_be_in_range() {
+        assert!(validate_estimate(None).is_ok());
+        assert!(validate_estimate(Some(1)).is_ok());
+        assert!(validate_estimate(Some(MAX_ESTIMATE)).is_ok());
+        assert!(validate_estimate(Some(0)).is_err());
+        assert!(validate_estimate(Some(MAX_ESTIMATE + 1)).is_err());
+        // Checked before the database is touched.
+        assert!(create("Read", Some(0)).is_err());
+    }
+
+    #[test]
+    fn renaming_rewrites_the_task_of_its_sessions() {
+        let mut conn = open();
+        let draft = insert(&conn, "Draft", None).unwrap().unwrap();
+        let other = insert(&conn, "Other", None).unwrap().unwrap();
+        focus_on(&conn, draft.id, 25);
+        focus_on(&conn, other.id, 25);
+        focus_on(&conn, draft.id, 50);
+
+        let renamed = update_name(&mut conn, draft.id, "Final draft")
+            .unwrap()
+            .unwrap();
+        assert_eq!((renamed.pomodoros, renamed.focus_minutes), (2, 75));
+        assert_eq!(
+            session_tasks(&conn),
+            ["Final draft", "Other", "Final draft"]
+        );
+    }
+
+    #[test]
+    fn archived_tasks_are_listed_only_when_asked_for() {
+        let conn = open();
+        let kept = insert(&conn, "Kept", None).unwrap().unwrap();
+        let done = insert(&conn, "Done", None).unwrap().unwrap();
+        conn.execute("UPDATE tasks SET archived = 1 WHERE id = ?1", [done.id])
+            .unwrap();
+
+        let ids = |tasks: Vec<Task>| tasks.iter().map(|t| t.id).collect::<Vec<_>>();
+        assert_eq!(ids(query(&conn, false).unwrap()), [kept.id]);
+        assert_eq!(ids(query(&conn, true).unwrap()), [done.id, kept.id]);
+    }
+}
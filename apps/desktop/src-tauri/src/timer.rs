@@ -0,0 +1,693 @@
+use crate::lifecycle::{self, LifecycleEvent};
+use serde::{Deserialize, Serialize};
+use std::sync::{Condvar, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+/// How often the ticker thread wakes up while a phase is running. Ticks are
+/// only emitted when the displayed second changes, so this just bounds the
+/// latency of a phase completing.
+const TICK_INTERVAL_MS: u64 = 250;
+
+/// The single timer engine shared by commands, the tray and the ticker thread.
+static ENGINE: Mutex<Engine> = Mutex::new(Engine::new());
+
+/// Signalled when the engine starts running, so the ticker thread can sleep
+/// while it's stopped.
+static STARTED: Condvar = Condvar::new();
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Phase {
+    Work,
+    ShortBreak,
+    LongBreak,
+}
+
+/// Durations and cycle behaviour, mirroring the popover's timer settings.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TimerConfig {
+    /// Length of a single focus session when pomodoro mode is off.
+    pub minutes: u32,
+    pub pomodoro_mode: bool,
+    pub work_minutes: u32,
+    pub short_break_minutes: u32,
+    pub long_break_minutes: u32,
+    /// Every Nth completed work session is followed by a long break.
+    pub long_break_interval: u32,
+}
+
+impl TimerConfig {
+    const DEFAULT: TimerConfig = TimerConfig {
+        minutes: 25,
+        pomodoro_mode: false,
+        work_minutes: 25,
+        short_break_minutes: 5,
+        long_break_minutes: 15,
+        long_break_interval: 4,
+    };
+}
+
+impl Default for TimerConfig {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Snapshot sent to the frontend with every "timer-tick" event.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimerState {
+    pub phase: Phase,
+    pub is_running: bool,
+    /// Whole seconds left in the current phase (rounded up).
+    pub time_left: u64,
+    /// Full length of the current phase in seconds.
+    pub total_time: u64,
+    pub completed_sessions: u32,
+    pub pomodoro_mode: bool,
+    /// Paused because the user walked away, waiting on `resolve_idle`.
+    pub idle_paused: bool,
+}
+
+/// Payload of the "timer-phase-change" event.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PhaseChange {
+    pub from: Phase,
+    pub to: Phase,
+    /// False when the phase was skipped rather than run to the end.
+    pub completed: bool,
+    pub completed_sessions: u32,
+    pub pomodoro_mode: bool,
+    /// Seconds the user was away during the phase that ended.
+    pub idle_seconds: u64,
+    /// Length of the phase that ended, including a one-off `start_for`.
+    pub minutes: u32,
+}
+
+impl PhaseChange {
+    /// The lifecycle event for this change, if any, with the state to report
+    /// it with: the phase that ended rather than `after`, the one now set up.
+    pub(crate) fn lifecycle_event(
+        &self,
+        after: &TimerState,
+    ) -> Option<(LifecycleEvent, TimerState)> {
+        if !self.completed || self.from != Phase::Work {
+            return None;
+        }
+        let ended = TimerState {
+            phase: self.from,
+            is_running: false,
+            time_left: 0,
+            total_time: self.minutes as u64 * 60,
+            ..after.clone()
+        };
+        Some((LifecycleEvent::FocusComplete, ended))
+    }
+}
+
+/// Work/shortBreak/longBreak state machine measured against a monotonic clock.
+///
+/// Time spent in the current phase is `elapsed` (banked on every pause) plus
+/// the time since `started_at` while running, so a throttled webview or a
+/// late tick never drifts the countdown.
+struct Engine {
+    config: TimerConfig,
+    /// Settings changed while a phase was running; applied when it ends.
+    pending_config: Option<TimerConfig>,
+    /// Length of this focus phase only, from `start_for`. Cleared when the
+    /// phase ends or is reset.
+    run_minutes: Option<u32>,
+    phase: Phase,
+    completed_sessions: u32,
+    elapsed: Duration,
+    started_at: Option<Instant>,
+    /// Time the user was away during this phase.
+    idle: Duration,
+    /// Set while paused for idleness: when the user went idle, and how much
+    /// of that idle time had already been counted before the pause.
+    idle_pause: Option<(Instant, Duration)>,
+}
+
+impl Engine {
+    const fn new() -> Self {
+        Self {
+            config: TimerConfig::DEFAULT,
+            pending_config: None,
+            run_minutes: None,
+            phase: Phase::Work,
+            completed_sessions: 0,
+            elapsed: Duration::ZERO,
+            started_at: None,
+            idle: Duration::ZERO,
+            idle_pause: None,
+        }
+    }
+
+    fn phase_duration(&self) -> Duration {
+        let minutes = if let Some(minutes) = self.run_minutes {
+            minutes
+        } else if !self.config.pomodoro_mode {
+            self.config.minutes
+        } else {
+            match self.phase {
+                Phase::Work => self.config.work_minutes,
+                Phase::ShortBreak => self.config.short_break_minutes,
+                Phase::LongBreak => self.config.long_break_minutes,
+            }
+        };
+        Duration::from_secs(minutes.max(1) as u64 * 60)
+    }
+
+    fn elapsed(&self) -> Duration {
+        match self.started_at {
+            Some(t) => self.elapsed + t.elapsed(),
+            None => self.elapsed,
+        }
+    }
+
+    fn remaining(&self) -> Duration {
+        self.phase_duration().saturating_sub(self.elapsed())
+    }
+
+    fn is_running(&self) -> bool {
+        self.started_at.is_some()
+    }
+
+    /// A focus phase has been started and not yet finished.
+    fn focus_underway(&self) -> bool {
+        self.phase == Phase::Work && !self.elapsed().is_zero()
+    }
+
+    /// Apply new settings. A running phase keeps its length; the new settings
+    /// take over when it is reset or ends.
+    fn configure(&mut self, config: TimerConfig) {
+        if self.is_running() {
+            self.pending_config = Some(config);
+            return;
+        }
+        self.pending_config = None;
+        if !config.pomodoro_mode {
+            self.phase = Phase::Work;
+        }
+        self.config = config;
+    }
+
+    /// Returns true if this began the phase rather than resuming it.
+    fn start(&mut self) -> bool {
+        // A plain resume after an idle pause keeps what was already counted
+        // and drops the rest.
+        if let Some((since, _)) = self.idle_pause.take() {
+            self.idle += since.elapsed();
+        }
+        if self.started_at.is_none() && !self.remaining().is_zero() {
+            self.started_at = Some(Instant::now());
+            return self.elapsed.is_zero();
+        }
+        false
+    }
+
+    /// Start a focus phase of `minutes` without changing the configured
+    /// length. Returns true if this began the phase.
+    fn start_for(&mut self, minutes: u32) -> Result<bool, String> {
+        if self.phase != Phase::Work {
+            return Err("a break is up next; minutes only sets a focus session's length".into());
+        }
+        if !self.elapsed().is_zero() {
+            return Err(
+                "a focus session is already under way; reset it to change its length".into(),
+            );
+        }
+        self.run_minutes = Some(minutes);
+        Ok(self.start())
+    }
+
+    fn pause(&mut self) {
+        if let Some(t) = self.started_at.take() {
+            self.elapsed += t.elapsed();
+        }
+    }
+
+    /// Pause a running focus phase because the user has been idle for
+    /// `idle`, part of which has already been counted.
+    fn pause_idle(&mut self, idle: Duration) -> bool {
+        if !self.is_running() || self.phase != Phase::Work {
+            return false;
+        }
+        self.pause();
+        let since = Instant::now()
+            .checked_sub(idle)
+            .unwrap_or_else(Instant::now);
+        self.idle_pause = Some((since, idle.min(self.elapsed)));
+        true
+    }
+
+    /// Settle an idle pause and resume: `keep` counts the whole time away as
+    /// focus, otherwise the idle time counted before the pause is taken back.
+    /// Returns the time away, or None if there was no idle pause.
+    fn resolve_idle(&mut self, keep: bool) -> Option<Duration> {
+        let (since, counted) = self.idle_pause.take()?;
+        let away = since.elapsed();
+        if keep {
+            self.elapsed += away.saturating_sub(counted);
+        } else {
+            self.elapsed = self.elapsed.saturating_sub(counted);
+        }
+        self.idle += away;
+        if self.remaining().is_zero() {
+            // Kept enough to finish the phase; let the ticker complete it.
+            self.started_at = Some(Instant::now());
+        } else {
+            self.start();
+        }
+        Some(away)
+    }
+
+    fn reset(&mut self) {
+        self.started_at = None;
+        self.elapsed = Duration::ZERO;
+        self.idle = Duration::ZERO;
+        self.idle_pause = None;
+        self.run_minutes = None;
+        if let Some(config) = self.pending_config.take() {
+            self.configure(config);
+        }
+    }
+
+    /// Move to the phase after the current one and stop the clock.
+    fn advance(&mut self, completed: bool) -> PhaseChange {
+        let from = self.phase;
+        let pomodoro_mode = self.config.pomodoro_mode;
+        let idle_seconds = self.idle.as_secs();
+        let minutes = (self.phase_duration().as_secs() / 60) as u32;
+        if completed && from == Phase::Work {
+            self.completed_sessions += 1;
+        }
+        self.phase = if !self.config.pomodoro_mode {
+            Phase::Work
+        } else {
+            match from {
+                Phase::Work => {
+                    let interval = self.config.long_break_interval.max(1);
+                    if completed && self.completed_sessions.is_multiple_of(interval) {
+                        Phase::LongBreak
+                    } else {
+                        Phase::ShortBreak
+                    }
+                }
+                Phase::ShortBreak | Phase::LongBreak => Phase::Work,
+            }
+        };
+        self.reset();
+        PhaseChange {
+            from,
+            to: self.phase,
+            completed,
+            completed_sessions: self.completed_sessions,
+            pomodoro_mode,
+            idle_seconds,
+            minutes,
+        }
+    }
+
+    /// Complete the current phase if its time has run out.
+    fn poll(&mut self) -> Option<PhaseChange> {
+        if self.is_running() && self.remaining().is_zero() {
+            Some(self.advance(true))
+        } else {
+            None
+        }
+    }
+
+    fn snapshot(&self) -> TimerState {
+        let remaining = self.remaining();
+        // Round up so "00:00" is only ever shown once the phase has ended.
+        let time_left = remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0);
+        TimerState {
+            phase: self.phase,
+            is_running: self.is_running(),
+            time_left,
+            total_time: self.phase_duration().as_secs(),
+            completed_sessions: self.completed_sessions,
+            pomodoro_mode: self.config.pomodoro_mode,
+            idle_paused: self.idle_pause.is_some(),
+        }
+    }
+}
+
+fn emit_state(handle: &AppHandle, state: &TimerState) {
+    let _ = handle.emit("timer-tick", state);
+    crate::tray::timer_changed(handle, state);
+}
+
+fn emit_phase_change(handle: &AppHandle, change: &PhaseChange, state: &TimerState) {
+    let _ = handle.emit("timer-phase-change", change);
+    if let Some((event, ended)) = change.lifecycle_event(state) {
+        lifecycle::notify(handle, event, &ended);
+    }
+}
+
+/// Current timer state.
+pub fn state() -> TimerState {
+    ENGINE.lock().unwrap().snapshot()
+}
+
+fn emit_started(handle: &AppHandle, began: bool, state: &TimerState) {
+    STARTED.notify_all();
+    emit_state(handle, state);
+    if began {
+        let event = match state.phase {
+            Phase::Work => LifecycleEvent::FocusStart,
+            Phase::ShortBreak | Phase::LongBreak => LifecycleEvent::BreakStart,
+        };
+        lifecycle::notify(handle, event, state);
+    }
+}
+
+/// Start (or resume) the current phase, optionally applying new settings first.
+pub fn start(handle: &AppHandle, config: Option<TimerConfig>) -> TimerState {
+    let (began, state) = {
+        let mut engine = ENGINE.lock().unwrap();
+        if let Some(config) = config {
+            engine.configure(config);
+        }
+        let began = engine.start();
+        (began, engine.snapshot())
+    };
+    emit_started(handle, began, &state);
+    state
+}
+
+/// Start a focus session of `minutes`, leaving the configured length alone
+/// for the sessions after it. Fails during a break or once the session has
+/// begun, since its length can't change then.
+pub fn start_for(handle: &AppHandle, minutes: u32) -> Result<TimerState, String> {
+    let (began, state) = {
+        let mut engine = ENGINE.lock().unwrap();
+        let began = engine.start_for(minutes)?;
+        (began, engine.snapshot())
+    };
+    emit_started(handle, began, &state);
+    Ok(state)
+}
+
+/// Pause the current phase, keeping the time already spent in it.
+pub fn pause(handle: &AppHandle) -> TimerState {
+    let state = {
+        let mut engine = ENGINE.lock().unwrap();
+        engine.pause();
+        engine.snapshot()
+    };
+    emit_state(handle, &state);
+    state
+}
+
+/// Pause a running focus phase because the user has been idle for `idle`.
+/// Returns false (and does nothing) if no focus phase was running.
+pub fn pause_idle(handle: &AppHandle, idle: Duration) -> bool {
+    let (paused, state) = {
+        let mut engine = ENGINE.lock().unwrap();
+        let paused = engine.pause_idle(idle);
+        (paused, engine.snapshot())
+    };
+    if paused {
+        emit_state(handle, &state);
+    }
+    paused
+}
+
+/// Resume after an idle pause, keeping or discarding the time away.
+pub fn resolve_idle(handle: &AppHandle, keep: bool) -> Result<TimerState, String> {
+    let state = {
+        let mut engine = ENGINE.lock().unwrap();
+        engine
+            .resolve_idle(keep)
+            .ok_or("the timer isn't paused for idleness")?;
+        engine.snapshot()
+    };
+    STARTED.notify_all();
+    emit_state(handle, &state);
+    Ok(state)
+}
+
+/// Move a running phase on by time its clock missed, e.g. while the system
+/// was suspended. The ticker completes the phase if that used it up.
+pub fn catch_up(handle: &AppHandle, missed: Duration) {
+    let state = {
+        let mut engine = ENGINE.lock().unwrap();
+        if !engine.is_running() {
+            return;
+        }
+        engine.elapsed += missed;
+        engine.snapshot()
+    };
+    emit_state(handle, &state);
+}
+
+/// When the current idle pause began, if the timer is in one.
+pub fn idle_since() -> Option<Instant> {
+    ENGINE.lock().unwrap().idle_pause.map(|(since, _)| since)
+}
+
+/// Stop and rewind the current phase, optionally applying new settings.
+pub fn reset(handle: &AppHandle, config: Option<TimerConfig>) -> TimerState {
+    let (stopped, state) = {
+        let mut engine = ENGINE.lock().unwrap();
+        let stopped = engine.focus_underway();
+        engine.reset();
+        if let Some(config) = config {
+            engine.configure(config);
+        }
+        (stopped, engine.snapshot())
+    };
+    emit_state(handle, &state);
+    if stopped {
+        lifecycle::notify(handle, LifecycleEvent::FocusStop, &state);
+    }
+    state
+}
+
+/// Apply changed timer settings. A stopped timer is rewound to the new
+/// durations; a running phase finishes with the old ones.
+pub fn configure(handle: &AppHandle, config: TimerConfig) -> TimerState {
+    let state = {
+        let mut engine = ENGINE.lock().unwrap();
+        let running = engine.is_running();
+        engine.configure(config);
+        if !running {
+            engine.reset();
+        }
+        engine.snapshot()
+    };
+    emit_state(handle, &state);
+    state
+}
+
+/// Abandon the current phase and move to the next one without counting it.
+pub fn skip(handle: &AppHandle) -> TimerState {
+    let (stopped, change, state) = {
+        let mut engine = ENGINE.lock().unwrap();
+        // Reported with the focus being stopped, not the break after it.
+        let stopped = engine.focus_underway().then(|| engine.snapshot());
+        let change = engine.advance(false);
+        (stopped, change, engine.snapshot())
+    };
+    emit_phase_change(handle, &change, &state);
+    emit_state(handle, &state);
+    if let Some(stopped) = stopped {
+        lifecycle::notify(handle, LifecycleEvent::FocusStop, &stopped);
+    }
+    state
+}
+
+/// Spawns the ticker thread that emits "timer-tick" once per displayed
+/// second and "timer-phase-change" when a running phase reaches zero. It
+/// sleeps until `STARTED` is signalled while the timer is stopped.
+pub fn start_ticker(handle: AppHandle) {
+    std::thread::spawn(move || {
+        let mut last_emitted: Option<u64> = None;
+        loop {
+            {
+                let engine = ENGINE.lock().unwrap();
+                if !engine.is_running() {
+                    last_emitted = None;
+                    drop(
+                        STARTED
+                            .wait_while(engine, |engine| !engine.is_running())
+                            .unwrap(),
+                    );
+                }
+            }
+            std::thread::sleep(Duration::from_millis(TICK_INTERVAL_MS));
+
+            let (change, state) = {
+                let mut engine = ENGINE.lock().unwrap();
+                if !engine.is_running() {
+                    continue;
+                }
+                let change = engine.poll();
+                (change, engine.snapshot())
+            };
+
+            if let Some(change) = change {
+                emit_phase_change(&handle, &change, &state);
+                emit_state(&handle, &state);
+                last_emitted = None;
+                continue;
+            }
+
+            if last_emitted != Some(state.time_left) {
+                last_emitted = Some(state.time_left);
+                emit_state(&handle, &state);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pomodoro(long_break_interval: u32) -> Engine {
+        let mut engine = Engine::new();
+        engine.configure(TimerConfig {
+            pomodoro_mode: true,
+            long_break_interval,
+            ..TimerConfig::DEFAULT
+        });
+        engine
+    }
+
+    /// Pretend the running phase started `secs` ago.
+    fn backdate(engine: &mut Engine, secs: u64) {
+        engine.started_at = Some(Instant::now() - Duration::from_secs(secs));
+    }
+
+    #[test]
+    fn cycles_to_a_long_break_every_interval() {
+        let mut engine = pomodoro(2);
+        let mut phases = Vec::new();
+        for _ in 0..5 {
+            phases.push(engine.advance(true).to);
+        }
+        assert_eq!(
+            phases,
+            [
+                Phase::ShortBreak,
+                Phase::Work,
+                Phase::LongBreak,
+                Phase::Work,
+                Phase::ShortBreak
+            ]
+        );
+        assert_eq!(engine.completed_sessions, 3);
+
+        // A skipped focus phase isn't counted towards the long break.
+        let mut engine = pomodoro(1);
+        let change = engine.advance(false);
+        assert_eq!(
+            (change.to, change.completed_sessions),
+            (Phase::ShortBreak, 0)
+        );
+    }
+
+    #[test]
+    fn completes_a_phase_once_its_time_is_used() {
+        let mut engine = pomodoro(4);
+        assert!(engine.start());
+        assert!(engine.poll().is_none());
+        backdate(&mut engine, 25 * 60);
+        let change = engine.poll().unwrap();
+        assert_eq!(
+            (change.from, change.to, change.minutes),
+            (Phase::Work, Phase::ShortBreak, 25)
+        );
+        assert!(change.completed);
+        assert!(!engine.is_running());
+        assert_eq!(engine.remaining(), Duration::from_secs(5 * 60));
+    }
+
+    #[test]
+    fn pause_and_resume_keep_the_time_spent() {
+        let mut engine = pomodoro(4);
+        assert!(engine.start());
+        backdate(&mut engine, 60);
+        engine.pause();
+        assert!(!engine.is_running());
+        let spent = engine.elapsed();
+        assert!(spent >= Duration::from_secs(60));
+        std::thread::sleep(Duration::from_millis(20));
+        assert_eq!(engine.elapsed(), spent);
+
+        // Resuming isn't beginning the phase again.
+        assert!(!engine.start());
+        assert!(engine.is_running());
+        assert!(engine.elapsed() >= spent);
+        assert!(engine.remaining() <= Duration::from_secs(24 * 60));
+    }
+
+    #[test]
+    fn start_for_sets_only_the_next_focus_length() {
+        let mut engine = pomodoro(4);
+        assert_eq!(engine.start_for(10), Ok(true));
+        assert_eq!(engine.snapshot().total_time, 10 * 60);
+        assert!(engine.start_for(15).is_err());
+
+        backdate(&mut engine, 10 * 60);
+        let change = engine.poll().unwrap();
+        assert_eq!(change.minutes, 10);
+        assert!(engine.start_for(10).is_err());
+
+        engine.advance(true);
+        assert_eq!(engine.snapshot().total_time, 25 * 60);
+    }
+
+    #[test]
+    fn focus_complete_reports_the_phase_that_ended() {
+        let mut engine = pomodoro(4);
+        engine.start_for(10).unwrap();
+        backdate(&mut engine, 10 * 60);
+        let change = engine.poll().unwrap();
+        let after = engine.snapshot();
+        assert_eq!(after.phase, Phase::ShortBreak);
+
+        let (event, ended) = change.lifecycle_event(&after).unwrap();
+        assert_eq!(event, LifecycleEvent::FocusComplete);
+        assert_eq!(ended.phase, Phase::Work);
+        assert_eq!((ended.total_time, ended.time_left), (10 * 60, 0));
+        assert_eq!(ended.completed_sessions, after.completed_sessions);
+
+        // Breaks ending, and focus being skipped, aren't completions.
+        assert!(engine.advance(true).lifecycle_event(&after).is_none());
+        assert!(engine.advance(false).lifecycle_event(&after).is_none());
+    }
+
+    #[test]
+    fn idle_time_is_kept_or_taken_back() {
+        for keep in [false, true] {
+            let mut engine = pomodoro(4);
+            engine.start();
+            backdate(&mut engine, 10 * 60);
+            assert!(engine.pause_idle(Duration::from_secs(2 * 60)));
+            assert!(engine.snapshot().idle_paused);
+            assert!(!engine.is_running());
+
+            let away = engine.resolve_idle(keep).unwrap();
+            assert!(away >= Duration::from_secs(2 * 60));
+            assert!(engine.is_running());
+            assert!(!engine.snapshot().idle_paused);
+            let minutes = engine.elapsed().as_secs() / 60;
+            assert_eq!(minutes, if keep { 10 } else { 8 });
+            assert!(engine.resolve_idle(keep).is_none());
+        }
+
+        // Only a running focus phase pauses for idleness.
+        let mut engine = pomodoro(4);
+        assert!(!engine.pause_idle(Duration::from_secs(60)));
+        engine.advance(true);
+        engine.start();
+        assert!(!engine.pause_idle(Duration::from_secs(60)));
+    }
+}
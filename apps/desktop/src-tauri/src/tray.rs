@@ -1,14 +1,87 @@
+use crate::settings::PALS;
+use crate::timer::{Phase, TimerState};
+use serde::Serialize;
+use std::sync::Mutex;
 use tauri::{
     image::Image,
-    menu::{MenuBuilder, MenuItemBuilder},
+    menu::{CheckMenuItemBuilder, Menu, MenuBuilder, MenuItemBuilder, SubmenuBuilder},
     tray::TrayIconBuilder,
-    webview::WebviewWindowBuilder,
-    Emitter, Manager,
+    AppHandle, Emitter, Manager,
 };
 
-pub fn create_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
-    let icon_bytes = include_bytes!("../icons/tray-icon.png");
-    let icon = Image::from_bytes(icon_bytes)?;
+pub const TRAY_ID: &str = "main-tray";
+/// Tasks offered under "Recent tasks".
+const RECENT_TASKS: usize = 5;
+const SNOOZE_MINUTES: [u32; 3] = [5, 15, 30];
+
+/// Everything the menu shows. It's rebuilt whenever this changes.
+#[derive(Clone, Debug, Default, PartialEq)]
+struct MenuState {
+    phase: Option<Phase>,
+    is_running: bool,
+    /// The current phase hasn't been started yet.
+    fresh: bool,
+    pal: usize,
+    /// Eye breaks can't be snoozed in strict mode.
+    strict_eye_breaks: bool,
+    tasks: Vec<String>,
+}
+
+/// What the menu currently shows.
+static MENU: Mutex<Option<MenuState>> = Mutex::new(None);
+
+/// Payload of the "tray-start-focus" event.
+#[derive(Clone, Debug, Serialize)]
+struct StartFocus {
+    /// Set when a recent task was picked.
+    task: Option<String>,
+}
+
+fn build_menu(app: &AppHandle, state: &MenuState) -> tauri::Result<Menu<tauri::Wry>> {
+    let in_break = matches!(state.phase, Some(Phase::ShortBreak | Phase::LongBreak));
+
+    let start_focus = MenuItemBuilder::with_id("start-focus", "Start Focus")
+        .enabled(!state.is_running && state.fresh)
+        .build(app)?;
+    let pause = MenuItemBuilder::with_id("pause", "Pause")
+        .enabled(state.is_running)
+        .build(app)?;
+    let resume = MenuItemBuilder::with_id("resume", "Resume")
+        .enabled(!state.is_running && !state.fresh)
+        .build(app)?;
+    let reset = MenuItemBuilder::with_id("reset", "Reset")
+        .enabled(!state.fresh)
+        .build(app)?;
+    let skip_break = MenuItemBuilder::with_id("skip-break", "Skip Break")
+        .enabled(in_break)
+        .build(app)?;
+
+    let mut recent = SubmenuBuilder::new(app, "Recent Tasks");
+    for (i, task) in state.tasks.iter().enumerate() {
+        recent = recent.item(&MenuItemBuilder::with_id(format!("task:{}", i), task).build(app)?);
+    }
+    let recent = recent.enabled(!state.tasks.is_empty()).build()?;
+
+    let mut pals = SubmenuBuilder::new(app, "Focus Pal");
+    for (i, pal) in PALS.iter().enumerate() {
+        let item = CheckMenuItemBuilder::with_id(
+            format!("pal:{}", i),
+            format!("{} {}", pal.icon, pal.name),
+        )
+        .checked(i == state.pal)
+        .build(app)?;
+        pals = pals.item(&item);
+    }
+    let pals = pals.build()?;
+
+    let mut snooze = SubmenuBuilder::new(app, "Snooze Eye Break");
+    for minutes in SNOOZE_MINUTES {
+        let item =
+            MenuItemBuilder::with_id(format!("snooze:{}", minutes), format!("{} min", minutes))
+                .build(app)?;
+        snooze = snooze.item(&item);
+    }
+    let snooze = snooze.enabled(!state.strict_eye_breaks).build()?;
 
     let history = MenuItemBuilder::with_id("history", "History")
         .build(app)?;
@@ -20,62 +93,192 @@ pub fn create_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
         .accelerator("CommandOrControl+Q")
         .build(app)?;
 
-    let menu = MenuBuilder::new(app)
+    MenuBuilder::new(app)
+        .item(&start_focus)
+        .item(&pause)
+        .item(&resume)
+        .item(&reset)
+        .item(&skip_break)
+        .separator()
+        .item(&recent)
+        .item(&pals)
+        .item(&snooze)
+        .separator()
         .item(&history)
         .item(&eye_break_now)
         .item(&about)
         .separator()
         .item(&quit)
-        .build()?;
+        .build()
+}
+
+/// Rebuild the menu if `update` changed what it shows.
+fn update(app: &AppHandle, update: impl FnOnce(&mut MenuState)) {
+    let next = {
+        let mut menu = MENU.lock().unwrap();
+        let Some(current) = menu.as_mut() else {
+            // Not created yet.
+            return;
+        };
+        let mut next = current.clone();
+        update(&mut next);
+        if next == *current {
+            return;
+        }
+        *current = next.clone();
+        next
+    };
+    rebuild(app, next);
+}
+
+fn rebuild(app: &AppHandle, state: MenuState) {
+    // Menus are built on the main thread; queue it there rather than block
+    // this one (the ticker, or a menu handler already on the main thread).
+    let handle = app.clone();
+    let _ = app.run_on_main_thread(move || {
+        let Some(tray) = handle.tray_by_id(TRAY_ID) else {
+            return;
+        };
+        if let Err(e) = build_menu(&handle, &state).and_then(|m| tray.set_menu(Some(m))) {
+            log::warn!("failed to rebuild the tray menu: {}", e);
+        }
+    });
+}
+
+/// Follow the timer. Called with every state it broadcasts.
+pub fn timer_changed(app: &AppHandle, timer: &TimerState) {
+    crate::tray_icon::update(app, timer);
+    update(app, |state| {
+        state.phase = Some(timer.phase);
+        state.is_running = timer.is_running;
+        state.fresh = timer.time_left == timer.total_time;
+    });
+}
+
+/// Pick up changed settings and session history.
+pub fn refresh(app: &AppHandle) {
+    let settings = crate::settings::load();
+    let tasks = crate::history::recent_tasks(RECENT_TASKS).unwrap_or_else(|e| {
+        log::warn!("failed to read recent tasks: {}", e);
+        Vec::new()
+    });
+    update(app, |state| {
+        state.pal = settings.pal;
+        state.strict_eye_breaks = settings.eye_break.strict_mode;
+        state.tasks = tasks;
+    });
+}
+
+/// Start a focus session from the tray, skipping a break that's up next.
+fn start_focus(app: &AppHandle, task: Option<String>) {
+    if crate::timer::state().phase != Phase::Work {
+        crate::timer::skip(app);
+    }
+    // Let the popover pick up the task before the session begins.
+    let _ = app.emit("tray-start-focus", StartFocus { task });
+    crate::timer::start(app, None);
+    let _ = crate::windows::show_popover(app, true);
+}
+
+fn on_menu_event(app: &AppHandle, id: &str) {
+    if let Some(index) = id.strip_prefix("task:") {
+        let task = index
+            .parse::<usize>()
+            .ok()
+            .and_then(|i| MENU.lock().unwrap().as_ref()?.tasks.get(i).cloned());
+        if let Some(task) = task {
+            start_focus(app, Some(task));
+        }
+        return;
+    }
+    if let Some(pal) = id
+        .strip_prefix("pal:")
+        .and_then(|i| i.parse::<usize>().ok())
+    {
+        let current = MENU.lock().unwrap().clone();
+        match current {
+            // Clicking the checked pal unticks it; put the tick back.
+            Some(state) if state.pal == pal => rebuild(app, state),
+            _ => {
+                if let Err(e) = crate::settings::update(app, serde_json::json!({ "pal": pal })) {
+                    log::warn!("failed to change pal: {}", e);
+                }
+            }
+        }
+        return;
+    }
+    if let Some(minutes) = id
+        .strip_prefix("snooze:")
+        .and_then(|m| m.parse::<u32>().ok())
+    {
+        // Same event the overlay's snooze buttons send.
+        let _ = app.emit(
+            "eye-break-snoozed",
+            serde_json::json!({ "minutes": minutes }),
+        );
+        return;
+    }
+    match id {
+        // Timer actions drive the Rust timer directly; the popover just
+        // follows along via "timer-tick" events.
+        "start-focus" => start_focus(app, None),
+        "pause" => {
+            crate::timer::pause(app);
+            let _ = app.emit("tray-pause", ());
+        }
+        "resume" => {
+            crate::timer::start(app, None);
+        }
+        "reset" => {
+            crate::timer::reset(app, None);
+            let _ = app.emit("tray-reset", ());
+        }
+        "skip-break" => {
+            crate::timer::skip(app);
+        }
+        "history" => {
+            crate::windows::open_history(app);
+        }
+        "eye-break-now" => {
+            if let Some(w) = app.get_webview_window("popover") {
+                let _ = w.emit("tray-eye-break-now", ());
+            }
+        }
+        "about" => {
+            let _ = crate::windows::show_popover(app, true);
+            if let Some(w) = app.get_webview_window("popover") {
+                let _ = w.emit("open-about", ());
+            }
+        }
+        "quit" => {
+            app.exit(0);
+        }
+        _ => {}
+    }
+}
 
-    let _tray = TrayIconBuilder::with_id("main-tray")
+pub fn create_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
+    let icon_bytes = include_bytes!("../icons/tray-icon.png");
+    let icon = Image::from_bytes(icon_bytes)?;
+
+    let handle = app.handle();
+    let state = MenuState {
+        fresh: true,
+        ..Default::default()
+    };
+    let menu = build_menu(handle, &state)?;
+    *MENU.lock().unwrap() = Some(state);
+
+    let _tray = TrayIconBuilder::with_id(TRAY_ID)
         .icon(icon)
         .icon_as_template(true)
         .tooltip("meow")
         .menu(&menu)
         .show_menu_on_left_click(true)
-        .on_menu_event(|app, event| match event.id().as_ref() {
-            "history" => {
-                // If the history window already exists, just focus it
-                if let Some(win) = app.get_webview_window("history") {
-                    let _ = win.set_focus();
-                } else {
-                    // Create a new small window for history
-                    if let Ok(win) = WebviewWindowBuilder::new(
-                        app,
-                        "history",
-                        tauri::WebviewUrl::App("history".into()),
-                    )
-                    .title("meow — History")
-                    .inner_size(350.0, 350.0)
-                    .resizable(false)
-                    .maximizable(false)
-                    .decorations(true)
-                    .center()
-                    .build()
-                    {
-                        #[cfg(target_os = "macos")]
-                        crate::platform::hide_zoom_button(&win);
-                    }
-                }
-            }
-            "eye-break-now" => {
-                if let Some(w) = app.get_webview_window("popover") {
-                    let _ = w.emit("tray-eye-break-now", ());
-                }
-            }
-            "about" => {
-                let _ = crate::windows::show_popover(app, true);
-                if let Some(w) = app.get_webview_window("popover") {
-                    let _ = w.emit("open-about", ());
-                }
-            }
-            "quit" => {
-                app.exit(0);
-            }
-            _ => {}
-        })
+        .on_menu_event(|app, event| on_menu_event(app, event.id().as_ref()))
         .build(app)?;
 
+    timer_changed(handle, &crate::timer::state());
+    refresh(handle);
     Ok(())
 }
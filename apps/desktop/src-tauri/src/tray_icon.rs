@@ -0,0 +1,195 @@
+use crate::timer::{Phase, TimerState};
+use crate::tray::TRAY_ID;
+use std::f64::consts::TAU;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tauri::{image::Image, AppHandle};
+
+/// Ring progress only redraws this often.
+const MIN_INTERVAL: Duration = Duration::from_secs(5);
+/// Ring thickness and the base icon's size, relative to the icon.
+const RING_WIDTH: f64 = 0.09;
+const INNER_SCALE: f64 = 0.6;
+/// Opacity of the not-yet-elapsed part of the ring, and of a paused ring.
+const TRACK_ALPHA: f64 = 0.3;
+const PAUSED_ALPHA: f64 = 0.6;
+
+/// The popover ring's colours, per phase.
+const WORK_COLOR: [u8; 3] = [0x63, 0x66, 0xf1];
+const SHORT_BREAK_COLOR: [u8; 3] = [0x34, 0xd3, 0x99];
+const LONG_BREAK_COLOR: [u8; 3] = [0xfb, 0xbf, 0x24];
+const TRACK_COLOR: [u8; 3] = [0x98, 0x98, 0x9d];
+
+/// What the icon currently shows, and when it was drawn.
+#[derive(Clone, Copy, PartialEq)]
+struct Shown {
+    /// None for the plain icon.
+    ring: Option<(Phase, bool)>,
+    /// Elapsed share of the phase, in thousandths.
+    progress: u32,
+}
+
+static SHOWN: Mutex<Option<(Shown, Instant)>> = Mutex::new(None);
+
+fn base() -> &'static Image<'static> {
+    static BASE: OnceLock<Image<'static>> = OnceLock::new();
+    BASE.get_or_init(|| {
+        Image::from_bytes(include_bytes!("../icons/tray-icon.png"))
+            .expect("bundled tray icon is a valid PNG")
+    })
+}
+
+/// Bilinearly sample `image` at (`x`, `y`) in its pixel coordinates, as
+/// premultiplied RGBA in 0..=1.
+fn sample(image: &Image, x: f64, y: f64) -> [f64; 4] {
+    let (w, h) = (image.width() as i64, image.height() as i64);
+    let rgba = image.rgba();
+    let pixel = |px: i64, py: i64| -> [f64; 4] {
+        if px < 0 || py < 0 || px >= w || py >= h {
+            return [0.0; 4];
+        }
+        let i = ((py * w + px) * 4) as usize;
+        let a = rgba[i + 3] as f64 / 255.0;
+        [
+            rgba[i] as f64 / 255.0 * a,
+            rgba[i + 1] as f64 / 255.0 * a,
+            rgba[i + 2] as f64 / 255.0 * a,
+            a,
+        ]
+    };
+    let (x, y) = (x - 0.5, y - 0.5);
+    let (x0, y0) = (x.floor(), y.floor());
+    let (fx, fy) = (x - x0, y - y0);
+    let (x0, y0) = (x0 as i64, y0 as i64);
+    let corners = [
+        (pixel(x0, y0), (1.0 - fx) * (1.0 - fy)),
+        (pixel(x0 + 1, y0), fx * (1.0 - fy)),
+        (pixel(x0, y0 + 1), (1.0 - fx) * fy),
+        (pixel(x0 + 1, y0 + 1), fx * fy),
+    ];
+    let mut out = [0.0; 4];
+    for (p, weight) in corners {
+        for c in 0..4 {
+            out[c] += p[c] * weight;
+        }
+    }
+    out
+}
+
+/// Draw `src` (premultiplied) over `dst` (premultiplied).
+fn over(dst: [f64; 4], src: [f64; 4]) -> [f64; 4] {
+    let keep = 1.0 - src[3];
+    [
+        src[0] + dst[0] * keep,
+        src[1] + dst[1] * keep,
+        src[2] + dst[2] * keep,
+        src[3] + dst[3] * keep,
+    ]
+}
+
+fn premultiply(color: [u8; 3], alpha: f64) -> [f64; 4] {
+    [
+        color[0] as f64 / 255.0 * alpha,
+        color[1] as f64 / 255.0 * alpha,
+        color[2] as f64 / 255.0 * alpha,
+        alpha,
+    ]
+}
+
+/// The base icon, shrunk to the middle, inside a ring filled clockwise from
+/// the top to `progress` (0..=1) in `color`.
+///
+/// On macOS the icon stays a template image, so only the alpha survives:
+/// the elapsed part of the ring is solid and the rest faint.
+fn render(base: &Image, progress: f64, color: [u8; 3], alpha: f64) -> Image<'static> {
+    let size = base.width().max(base.height());
+    let n = size as f64;
+    let center = n / 2.0;
+    let width = n * RING_WIDTH;
+    let mid = center - 0.5 - width / 2.0;
+    let inner = n * INNER_SCALE;
+    let scale = base.width() as f64 / inner;
+    let offset = (n - inner) / 2.0;
+
+    let mut rgba = Vec::with_capacity((size * size * 4) as usize);
+    for y in 0..size {
+        for x in 0..size {
+            let (px, py) = (x as f64 + 0.5, y as f64 + 0.5);
+            let mut out = sample(base, (px - offset) * scale, (py - offset) * scale);
+
+            let (dx, dy) = (px - center, py - center);
+            let coverage =
+                (width / 2.0 + 0.5 - ((dx * dx + dy * dy).sqrt() - mid).abs()).clamp(0.0, 1.0);
+            if coverage > 0.0 {
+                // Clockwise from 12 o'clock, as a share of a full turn.
+                let turn = dx.atan2(-dy).rem_euclid(TAU) / TAU;
+                let ring = if turn <= progress {
+                    premultiply(color, alpha * coverage)
+                } else {
+                    premultiply(TRACK_COLOR, TRACK_ALPHA * coverage)
+                };
+                out = over(out, ring);
+            }
+
+            let a = out[3];
+            let unpremultiply = |c: f64| {
+                if a > 0.0 {
+                    (c / a * 255.0).round() as u8
+                } else {
+                    0
+                }
+            };
+            rgba.extend_from_slice(&[
+                unpremultiply(out[0]),
+                unpremultiply(out[1]),
+                unpremultiply(out[2]),
+                (a * 255.0).round() as u8,
+            ]);
+        }
+    }
+    Image::new_owned(rgba, size, size)
+}
+
+/// Redraw the tray icon for `state`: a progress ring while a phase is under
+/// way, the plain icon otherwise. Starting, pausing or changing phase shows
+/// at once; progress alone redraws at most every `MIN_INTERVAL`.
+pub fn update(app: &AppHandle, state: &TimerState) {
+    let underway = state.is_running || state.time_left < state.total_time;
+    let elapsed = state.total_time.saturating_sub(state.time_left);
+    let next = Shown {
+        ring: underway.then_some((state.phase, state.is_running)),
+        progress: (elapsed * 1000 / state.total_time.max(1)) as u32,
+    };
+    {
+        let mut shown = SHOWN.lock().unwrap();
+        if let Some((current, drawn_at)) = *shown {
+            let due = current.ring != next.ring
+                || (current.progress != next.progress && drawn_at.elapsed() >= MIN_INTERVAL);
+            if !due {
+                return;
+            }
+        }
+        *shown = Some((next, Instant::now()));
+    }
+
+    let icon = match next.ring {
+        None => base().clone(),
+        Some((phase, running)) => {
+            let color = match phase {
+                Phase::Work => WORK_COLOR,
+                Phase::ShortBreak => SHORT_BREAK_COLOR,
+                Phase::LongBreak => LONG_BREAK_COLOR,
+            };
+            let alpha = if running { 1.0 } else { PAUSED_ALPHA };
+            render(base(), next.progress as f64 / 1000.0, color, alpha)
+        }
+    };
+    let handle = app.clone();
+    let _ = app.run_on_main_thread(move || {
+        if let Some(tray) = handle.tray_by_id(TRAY_ID) {
+            if let Err(e) = tray.set_icon(Some(icon)) {
+                log::warn!("failed to update the tray icon: {}", e);
+            }
+        }
+    });
+}
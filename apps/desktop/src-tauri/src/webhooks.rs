@@ -0,0 +1,443 @@
+use crate::db;
+use crate::history::format_date;
+use crate::lifecycle::{self, LifecycleEvent};
+use crate::timer::TimerState;
+use chrono::Utc;
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::sync::OnceLock;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Url};
+
+const MAX_WEBHOOKS: usize = 20;
+const MAX_RETRIES: u32 = 10;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// Wait before the first retry; doubled for each one after.
+const FIRST_BACKOFF: Duration = Duration::from_secs(2);
+/// Deliveries kept in the log.
+const LOG_SIZE: i64 = 200;
+
+static AGENT: OnceLock<ureq::Agent> = OnceLock::new();
+
+/// An outgoing HTTP POST fired on session lifecycle events.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Webhook {
+    pub enabled: bool,
+    pub url: String,
+    /// Events to fire on; empty means all of them.
+    pub events: Vec<LifecycleEvent>,
+    /// JSON body with `{{placeholders}}` named after `lifecycle::fields`. Empty sends
+    /// the default payload: the event, the date and the timer state.
+    pub template: String,
+    /// Extra attempts after a failed one, with exponential backoff.
+    pub retries: u32,
+}
+
+impl Default for Webhook {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            url: String::new(),
+            events: Vec::new(),
+            template: String::new(),
+            retries: 3,
+        }
+    }
+}
+
+/// One entry in the delivery log.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Delivery {
+    pub id: i64,
+    pub url: String,
+    pub event: String,
+    pub attempts: u32,
+    /// HTTP status of the last attempt, if the server answered.
+    pub status: Option<u16>,
+    /// Why the last attempt failed, if it did.
+    pub error: Option<String>,
+    pub date: String,
+}
+
+/// Fill in `{{name}}` placeholders. Values are JSON-escaped so they can sit
+/// inside strings; numbers and booleans also work bare. The result must be
+/// valid JSON.
+fn substitute(template: &str, fields: &[(&str, String)]) -> Result<String, String> {
+    let mut body = template.to_string();
+    for (name, value) in fields {
+        let escaped = Value::String(value.clone()).to_string();
+        body = body.replace(&format!("{{{{{}}}}}", name), &escaped[1..escaped.len() - 1]);
+    }
+    serde_json::from_str::<Value>(&body)
+        .map_err(|e| format!("template isn't valid JSON: {}", e))?;
+    Ok(body)
+}
+
+/// The request body for `event`.
+fn render(template: &str, event: LifecycleEvent, state: &TimerState) -> Result<String, String> {
+    if template.trim().is_empty() {
+        let body = json!({ "event": event, "date": format_date(&Utc::now()), "state": state });
+        return Ok(body.to_string());
+    }
+    substitute(template, &lifecycle::fields(event, state))
+}
+
+/// Whether `hook` should fire on `event`.
+fn wants(hook: &Webhook, event: LifecycleEvent) -> bool {
+    hook.enabled && (hook.events.is_empty() || hook.events.contains(&event))
+}
+
+/// Check each webhook has an http(s) URL and a template that renders to JSON.
+pub fn validate(webhooks: &[Webhook]) -> Result<(), String> {
+    if webhooks.len() > MAX_WEBHOOKS {
+        return Err(format!("at most {} webhooks", MAX_WEBHOOKS));
+    }
+    let sample = crate::timer::state();
+    for hook in webhooks {
+        let url = Url::parse(&hook.url)
+            .map_err(|e| format!("invalid webhook URL \"{}\": {}", hook.url, e))?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(format!("webhook URL must be http or https: {}", hook.url));
+        }
+        if hook.retries > MAX_RETRIES {
+            return Err(format!("webhook retries must be at most {}", MAX_RETRIES));
+        }
+        render(&hook.template, LifecycleEvent::FocusStart, &sample)?;
+    }
+    Ok(())
+}
+
+/// Send once. Returns the status on success, or the status (if any) and
+/// error of a failure, and whether it's worth retrying.
+fn attempt(url: &str, body: &str) -> Result<u16, (Option<u16>, String, bool)> {
+    let agent = AGENT.get_or_init(|| ureq::AgentBuilder::new().timeout(REQUEST_TIMEOUT).build());
+    match agent
+        .post(url)
+        .set("Content-Type", "application/json")
+        .send_string(body)
+    {
+        Ok(response) => Ok(response.status()),
+        // Client errors won't go away by asking again, except rate limiting.
+        Err(ureq::Error::Status(code, response)) => Err((
+            Some(code),
+            response.status_text().to_string(),
+            code >= 500 || code == 429,
+        )),
+        Err(e) => Err((None, e.to_string(), true)),
+    }
+}
+
+fn log_delivery(
+    url: &str,
+    event: LifecycleEvent,
+    attempts: u32,
+    status: Option<u16>,
+    error: Option<&str>,
+) -> Result<Delivery, String> {
+    let date = format_date(&Utc::now());
+    db::with_conn(|conn| {
+        conn.execute(
+            "INSERT INTO webhook_deliveries (url, event, attempts, status, error, date)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![url, event.as_str(), attempts, status, error, date],
+        )?;
+        let id = conn.last_insert_rowid();
+        conn.execute(
+            "DELETE FROM webhook_deliveries WHERE id <= ?1",
+            [id - LOG_SIZE],
+        )?;
+        Ok(Delivery {
+            id,
+            url: url.to_string(),
+            event: event.as_str().to_string(),
+            attempts,
+            status,
+            error: error.map(str::to_string),
+            date,
+        })
+    })
+}
+
+/// POST `body` to the hook, retrying with backoff from `first_backoff`.
+/// Returns the attempts made, the last status and the last error, if any.
+fn send(hook: &Webhook, body: &str, first_backoff: Duration) -> (u32, Option<u16>, Option<String>) {
+    let mut backoff = first_backoff;
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+        match attempt(&hook.url, body) {
+            Ok(status) => return (attempts, Some(status), None),
+            Err((status, error, retry)) => {
+                if !retry || attempts > hook.retries {
+                    return (attempts, status, Some(error));
+                }
+                std::thread::sleep(backoff);
+                backoff *= 2;
+            }
+        }
+    }
+}
+
+/// Send `body` to the hook, then log the outcome and broadcast it as
+/// "webhook-delivery".
+fn deliver(app: &AppHandle, hook: &Webhook, event: LifecycleEvent, body: &str) {
+    let (attempts, status, error) = send(hook, body, FIRST_BACKOFF);
+    if let Some(e) = &error {
+        log::warn!(
+            "webhook {} failed after {} attempts: {}",
+            hook.url,
+            attempts,
+            e
+        );
+    }
+    match log_delivery(&hook.url, event, attempts, status, error.as_deref()) {
+        Ok(delivery) => {
+            let _ = app.emit("webhook-delivery", &delivery);
+        }
+        Err(e) => log::warn!("failed to log webhook delivery: {}", e),
+    }
+}
+
+/// The webhooks of `hooks` that want `event`, each with its request body.
+fn requests(
+    hooks: Vec<Webhook>,
+    event: LifecycleEvent,
+    state: &TimerState,
+) -> Vec<(Webhook, String)> {
+    hooks
+        .into_iter()
+        .filter(|hook| wants(hook, event))
+        .filter_map(|hook| match render(&hook.template, event, state) {
+            Ok(body) => Some((hook, body)),
+            Err(e) => {
+                log::warn!("webhook {}: {}", hook.url, e);
+                None
+            }
+        })
+        .collect()
+}
+
+/// Fire every enabled webhook that wants `event`, each on its own thread.
+pub fn fire(app: &AppHandle, event: LifecycleEvent, state: &TimerState) {
+    for (hook, body) in requests(crate::settings::load().webhooks, event, state) {
+        let app = app.clone();
+        std::thread::spawn(move || deliver(&app, &hook, event, &body));
+    }
+}
+
+/// The most recent deliveries, newest first.
+pub fn deliveries(limit: usize) -> Result<Vec<Delivery>, String> {
+    db::with_conn(|conn| {
+        let mut stmt = conn.prepare(
+            "SELECT id, url, event, attempts, status, error, date
+             FROM webhook_deliveries ORDER BY id DESC LIMIT ?1",
+        )?;
+        let rows = stmt.query_map([limit as i64], |row| {
+            Ok(Delivery {
+                id: row.get(0)?,
+                url: row.get(1)?,
+                event: row.get(2)?,
+                attempts: row.get(3)?,
+                status: row.get(4)?,
+                error: row.get(5)?,
+                date: row.get(6)?,
+            })
+        })?;
+        rows.collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::timer::{Phase, PhaseChange};
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+    use tiny_http::{Response, Server};
+
+    const NO_WAIT: Duration = Duration::from_millis(1);
+
+    /// A local server answering each request with the next of `statuses`
+    /// (repeating the last). Returns its URL and a count of requests seen.
+    fn stand_in(statuses: &'static [u16]) -> (String, Arc<AtomicU32>) {
+        let server = Server::http("127.0.0.1:0").unwrap();
+        let port = server.server_addr().to_ip().unwrap().port();
+        let seen = Arc::new(AtomicU32::new(0));
+        let counter = seen.clone();
+        std::thread::spawn(move || {
+            for request in server.incoming_requests() {
+                let n = counter.fetch_add(1, Ordering::SeqCst) as usize;
+                let status = statuses[n.min(statuses.len() - 1)];
+                let _ = request.respond(Response::empty(status));
+            }
+        });
+        (format!("http://127.0.0.1:{}/hook", port), seen)
+    }
+
+    fn hook(url: &str, retries: u32) -> Webhook {
+        Webhook {
+            url: url.to_string(),
+            retries,
+            ..Default::default()
+        }
+    }
+
+    fn state() -> TimerState {
+        TimerState {
+            phase: Phase::Work,
+            is_running: true,
+            time_left: 1500,
+            total_time: 1500,
+            completed_sessions: 2,
+            pomodoro_mode: true,
+            idle_paused: false,
+        }
+    }
+
+    #[test]
+    fn stand_in_server_answers() {
+        let (url, seen) = stand_in(&[204]);
+        assert_eq!(attempt(&url, "{}"), Ok(204));
+        assert_eq!(seen.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn render_fills_and_escapes_placeholders() {
+        let body = substitute(
+            r#"{"text": "{{task}}", "n": {{count}}}"#,
+            &[
+                ("task", "say \"hi\"\n".to_string()),
+                ("count", "3".to_string()),
+            ],
+        )
+        .unwrap();
+        let value: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(value, json!({ "text": "say \"hi\"\n", "n": 3 }));
+
+        let body = render(
+            r#"{"event": "{{event}}", "minutes": {{minutes}}, "running": {{isRunning}}}"#,
+            LifecycleEvent::FocusStart,
+            &state(),
+        )
+        .unwrap();
+        let value: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(
+            value,
+            json!({ "event": "focusStart", "minutes": 25, "running": true })
+        );
+
+        let body = render("", LifecycleEvent::BreakStart, &state()).unwrap();
+        let value: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(value["event"], "breakStart");
+        assert_eq!(value["state"]["completedSessions"], 2);
+
+        assert!(render(
+            r#"{"text": {{event}}}"#,
+            LifecycleEvent::FocusStart,
+            &state()
+        )
+        .is_err());
+        assert!(render("{\"text\": ", LifecycleEvent::FocusStart, &state()).is_err());
+    }
+
+    #[test]
+    fn delivery_succeeds_on_200() {
+        let (url, seen) = stand_in(&[200]);
+        assert_eq!(send(&hook(&url, 3), "{}", NO_WAIT), (1, Some(200), None));
+        assert_eq!(seen.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn client_errors_are_not_retried() {
+        let (url, seen) = stand_in(&[404]);
+        let (attempts, status, error) = send(&hook(&url, 3), "{}", NO_WAIT);
+        assert_eq!((attempts, status), (1, Some(404)));
+        assert!(error.is_some());
+        assert_eq!(seen.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn server_errors_and_rate_limits_are_retried() {
+        let (url, seen) = stand_in(&[500]);
+        let (attempts, status, error) = send(&hook(&url, 2), "{}", NO_WAIT);
+        assert_eq!((attempts, status), (3, Some(500)));
+        assert!(error.is_some());
+        assert_eq!(seen.load(Ordering::SeqCst), 3);
+
+        let (url, seen) = stand_in(&[429, 503, 200]);
+        assert_eq!(send(&hook(&url, 5), "{}", NO_WAIT), (3, Some(200), None));
+        assert_eq!(seen.load(Ordering::SeqCst), 3);
+    }
+
+    /// The URLs `fire` would post to for `event`.
+    fn sent_to(hooks: &[Webhook], event: LifecycleEvent) -> Vec<String> {
+        requests(hooks.to_vec(), event, &state())
+            .into_iter()
+            .map(|(hook, _)| hook.url)
+            .collect()
+    }
+
+    #[test]
+    fn fire_skips_hooks_filtered_out_or_disabled() {
+        let all = hook("http://127.0.0.1/all", 0);
+        let some = Webhook {
+            url: "http://127.0.0.1/some".to_string(),
+            events: vec![LifecycleEvent::FocusComplete],
+            ..all.clone()
+        };
+        let disabled = Webhook {
+            url: "http://127.0.0.1/disabled".to_string(),
+            enabled: false,
+            ..all.clone()
+        };
+        let hooks = [all, some, disabled];
+
+        assert_eq!(
+            sent_to(&hooks, LifecycleEvent::FocusStart),
+            ["http://127.0.0.1/all"]
+        );
+        assert_eq!(
+            sent_to(&hooks, LifecycleEvent::FocusComplete),
+            ["http://127.0.0.1/all", "http://127.0.0.1/some"]
+        );
+        assert!(sent_to(&hooks[1..], LifecycleEvent::EyeBreakEnd).is_empty());
+    }
+
+    #[test]
+    fn focus_complete_describes_the_finished_focus() {
+        // The timer has already moved on to the break when focus completes.
+        let after = TimerState {
+            phase: Phase::ShortBreak,
+            is_running: false,
+            time_left: 300,
+            total_time: 300,
+            completed_sessions: 3,
+            pomodoro_mode: true,
+            idle_paused: false,
+        };
+        let change = PhaseChange {
+            from: Phase::Work,
+            to: Phase::ShortBreak,
+            completed: true,
+            completed_sessions: 3,
+            pomodoro_mode: true,
+            idle_seconds: 0,
+            minutes: 50,
+        };
+        let (event, ended) = change.lifecycle_event(&after).unwrap();
+        let hook = Webhook {
+            template: r#"{"event": "{{event}}", "phase": "{{phase}}", "minutes": {{minutes}}, "done": {{completedSessions}}}"#.to_string(),
+            ..hook("http://127.0.0.1/", 0)
+        };
+        let sent = requests(vec![hook], event, &ended);
+        assert_eq!(sent.len(), 1);
+        let value: Value = serde_json::from_str(&sent[0].1).unwrap();
+        assert_eq!(
+            value,
+            json!({ "event": "focusComplete", "phase": "work", "minutes": 50, "done": 3 })
+        );
+    }
+}
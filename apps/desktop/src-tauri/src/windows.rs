@@ -1,6 +1,9 @@
+use crate::lifecycle::{self, LifecycleEvent};
+use serde::{Deserialize, Serialize};
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Mutex;
-use tauri::{AppHandle, Emitter, Manager};
+use tauri::webview::WebviewWindowBuilder;
+use tauri::{AppHandle, Emitter, Manager, Monitor, WebviewWindow, WindowEvent};
 
 /// Tracks whether the popover is EXPANDED (not whether the window is visible).
 /// The window is always visible as a notch pill; this just tracks expand/collapse.
@@ -15,19 +18,82 @@ const EXPAND_COOLDOWN_MS: u64 = 500;
 const COLLAPSED_WIDTH: f64 = 300.0; // wider than notch so content sits on both wings
 const EXPANDED_WIDTH: f64 = 350.0;
 
+/// Label prefix of the eye break overlays; each gets "-<monitor index>".
+const EYE_BREAK_LABEL: &str = "eyebreak";
+
+/// URL and strictness of the open eye break, so overlays can be added when a
+/// monitor is plugged in mid-break.
+static EYE_BREAK: Mutex<Option<(String, bool)>> = Mutex::new(None);
+
+/// Bumped on every display change; a re-layout only runs once it settles.
+static DISPLAY_GENERATION: AtomicU64 = AtomicU64::new(0);
+const DISPLAY_SETTLE_MS: u64 = 300;
+
+/// Which monitor the pill sits on.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PillMonitor {
+    #[default]
+    Primary,
+    /// Whichever monitor the cursor is on when the pill is placed or expanded.
+    Cursor,
+    /// A monitor by name, falling back to the primary one while it's unplugged.
+    Named(String),
+}
+
+/// The monitor the pill belongs on according to the `pillMonitor` setting.
+fn pill_monitor(handle: &AppHandle) -> Result<Monitor, Box<dyn std::error::Error>> {
+    let monitor = match crate::settings::load().pill_monitor {
+        PillMonitor::Primary => None,
+        PillMonitor::Cursor => handle
+            .cursor_position()
+            .ok()
+            .and_then(|p| handle.monitor_from_point(p.x, p.y).ok().flatten()),
+        PillMonitor::Named(name) => handle
+            .available_monitors()?
+            .into_iter()
+            .find(|m| m.name() == Some(&name)),
+    };
+    match monitor {
+        Some(monitor) => Ok(monitor),
+        None => Ok(handle.primary_monitor()?.ok_or("no primary monitor")?),
+    }
+}
+
+/// Names of the connected monitors, for the `pillMonitor` setting.
+pub fn monitor_names(handle: &AppHandle) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    Ok(handle
+        .available_monitors()?
+        .into_iter()
+        .filter_map(|m| m.name().cloned())
+        .collect())
+}
+
 /// Position the always-visible notch pill at startup and set it above the menu bar.
 pub fn setup_windows(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
     if let Some(win) = app.get_webview_window("popover") {
-        #[cfg(target_os = "macos")]
+        #[cfg(any(target_os = "macos", target_os = "linux"))]
         crate::platform::set_above_menu_bar(&win);
 
         // Start collapsed at notch width
         let _ = win.set_size(tauri::LogicalSize::new(COLLAPSED_WIDTH, 37.0));
 
-        let monitor = app.primary_monitor()?.ok_or("no primary monitor")?;
-        let scale = monitor.scale_factor();
-        let sw = monitor.size().width as f64 / scale;
-        win.set_position(tauri::LogicalPosition::new(sw / 2.0 - COLLAPSED_WIDTH / 2.0, 0.0))?;
+        let monitor = pill_monitor(app.handle())?;
+        center_popover(app.handle(), &monitor, COLLAPSED_WIDTH)?;
+
+        // Created hidden on Linux (tauri.linux.conf.json) so layer-shell can
+        // be set up above before it's first shown.
+        #[cfg(target_os = "linux")]
+        win.show()?;
+
+        // Moving between monitors of different density, or changing the
+        // display scale, shifts the pill's logical size and position.
+        let h = app.handle().clone();
+        win.on_window_event(move |event| {
+            if let WindowEvent::ScaleFactorChanged { .. } = event {
+                displays_changed(&h);
+            }
+        });
     }
 
     // Re-apply NSWindow settings after 1s on the main thread, to override
@@ -40,8 +106,10 @@ pub fn setup_windows(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>>
         let h2 = h.clone();
         let _ = h.run_on_main_thread(move || {
             if let Some(win) = h2.get_webview_window("popover") {
-                #[cfg(target_os = "macos")]
+                #[cfg(any(target_os = "macos", target_os = "linux"))]
                 crate::platform::set_above_menu_bar(&win);
+                #[cfg(target_os = "linux")]
+                crate::platform::prevent_window_hiding(&win);
             }
         });
     });
@@ -49,16 +117,55 @@ pub fn setup_windows(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>>
     Ok(())
 }
 
-/// Re-center the popover window horizontally at the given width.
-fn center_popover(handle: &AppHandle, width: f64) -> Result<(), Box<dyn std::error::Error>> {
+/// Re-center the popover window horizontally at the given width, at the top
+/// of `monitor`.
+fn center_popover(
+    handle: &AppHandle,
+    monitor: &Monitor,
+    width: f64,
+) -> Result<(), Box<dyn std::error::Error>> {
     let win = handle.get_webview_window("popover").ok_or("popover not found")?;
-    let monitor = handle.primary_monitor()?.ok_or("no primary monitor")?;
-    let scale = monitor.scale_factor();
-    let sw = monitor.size().width as f64 / scale;
-    win.set_position(tauri::LogicalPosition::new(sw / 2.0 - width / 2.0, 0.0))?;
+    // Physical units, since monitors side by side can have different scales.
+    let origin = monitor.position();
+    let x = origin.x as f64 + (monitor.size().width as f64 - width * monitor.scale_factor()) / 2.0;
+    win.set_position(tauri::PhysicalPosition::new(x, origin.y as f64))?;
+    // On Wayland the pill is a layer surface, which only the compositor moves.
+    #[cfg(target_os = "linux")]
+    crate::platform::set_layer_monitor(&win, monitor);
     Ok(())
 }
 
+/// Move the pill to the monitor the `pillMonitor` setting picks, keeping its
+/// current expanded or collapsed width.
+pub fn place_popover(handle: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
+    let width = if POPOVER_VISIBLE.load(Ordering::SeqCst) {
+        EXPANDED_WIDTH
+    } else {
+        COLLAPSED_WIDTH
+    };
+    center_popover(handle, &pill_monitor(handle)?, width)
+}
+
+/// Re-home the pill and re-lay out any eye break overlays after monitors are
+/// added, removed, rearranged or rescaled. Platform observers call this for
+/// every change notification; the work runs once things have settled.
+pub fn displays_changed(handle: &AppHandle) {
+    let generation = DISPLAY_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    let h = handle.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(std::time::Duration::from_millis(DISPLAY_SETTLE_MS));
+        if DISPLAY_GENERATION.load(Ordering::SeqCst) != generation {
+            return;
+        }
+        if let Err(e) = place_popover(&h) {
+            log::warn!("failed to re-home the pill: {}", e);
+        }
+        if let Err(e) = layout_eye_breaks(&h) {
+            log::warn!("failed to re-lay out eye break overlays: {}", e);
+        }
+    });
+}
+
 /// Expand the popover card. Emits "popover-expand" to the frontend.
 pub fn show_popover(handle: &AppHandle, focus: bool) -> Result<(), Box<dyn std::error::Error>> {
     reset_outside_count();
@@ -73,7 +180,7 @@ pub fn show_popover(handle: &AppHandle, focus: bool) -> Result<(), Box<dyn std::
     if let Some(win) = handle.get_webview_window("popover") {
         // Widen to expanded size and re-center
         let _ = win.set_size(tauri::LogicalSize::new(EXPANDED_WIDTH, 50.0));
-        center_popover(handle, EXPANDED_WIDTH)?;
+        center_popover(handle, &pill_monitor(handle)?, EXPANDED_WIDTH)?;
 
         let _ = win.emit("popover-expand", ());
         POPOVER_VISIBLE.store(true, Ordering::SeqCst);
@@ -102,8 +209,11 @@ pub fn hide_popover(handle: &AppHandle, _immediate: bool) -> Result<(), Box<dyn
             if !POPOVER_VISIBLE.load(Ordering::SeqCst) {
                 if let Some(w) = h.get_webview_window("popover") {
                     let _ = w.set_size(tauri::LogicalSize::new(COLLAPSED_WIDTH, 37.0));
+                    // Stay on the monitor it expanded on
+                    if let Some(monitor) = w.current_monitor().ok().flatten() {
+                        let _ = center_popover(&h, &monitor, COLLAPSED_WIDTH);
+                    }
                 }
-                let _ = center_popover(&h, COLLAPSED_WIDTH);
             }
         });
     }
@@ -138,3 +248,165 @@ pub fn expand_cooldown_elapsed() -> bool {
     let last = LAST_EXPAND_MS.load(Ordering::SeqCst);
     now.saturating_sub(last) >= EXPAND_COOLDOWN_MS
 }
+
+/// Open the history window, or focus it if it's already open.
+pub fn open_history(handle: &AppHandle) {
+    if let Some(win) = handle.get_webview_window("history") {
+        let _ = win.set_focus();
+        return;
+    }
+    // Create a new small window for history
+    if let Ok(win) =
+        WebviewWindowBuilder::new(handle, "history", tauri::WebviewUrl::App("history".into()))
+            .title("meow — History")
+            .inner_size(350.0, 350.0)
+            .resizable(false)
+            .maximizable(false)
+            .decorations(true)
+            .center()
+            .build()
+    {
+        #[cfg(target_os = "macos")]
+        crate::platform::hide_zoom_button(&win);
+        #[cfg(not(target_os = "macos"))]
+        let _ = win;
+    }
+}
+
+/// Open eye break overlays with their monitor index, in monitor order.
+fn eye_break_windows(handle: &AppHandle) -> Vec<(usize, WebviewWindow)> {
+    let mut windows: Vec<(usize, WebviewWindow)> = handle
+        .webview_windows()
+        .into_iter()
+        .filter_map(|(label, win)| {
+            let index = label
+                .strip_prefix(EYE_BREAK_LABEL)?
+                .strip_prefix('-')?
+                .parse()
+                .ok()?;
+            Some((index, win))
+        })
+        .collect();
+    windows.sort_by_key(|(index, _)| *index);
+    windows
+}
+
+/// Stretch a window over the whole of `monitor`, in physical units since
+/// monitors can have different scale factors.
+fn cover_monitor(win: &WebviewWindow, monitor: &Monitor) -> Result<(), Box<dyn std::error::Error>> {
+    win.set_position(*monitor.position())?;
+    win.set_size(*monitor.size())?;
+    // Strict overlays are layer surfaces on Wayland, placed by output.
+    #[cfg(target_os = "linux")]
+    crate::platform::set_layer_monitor(win, monitor);
+    Ok(())
+}
+
+fn build_eye_break(
+    handle: &AppHandle,
+    index: usize,
+    monitor: &Monitor,
+    url: &str,
+    strict: bool,
+) -> Result<WebviewWindow, Box<dyn std::error::Error>> {
+    let win = WebviewWindowBuilder::new(
+        handle,
+        format!("{}-{}", EYE_BREAK_LABEL, index),
+        tauri::WebviewUrl::App(url.into()),
+    )
+    .title("")
+    .decorations(false)
+    .resizable(false)
+    .always_on_top(true)
+    .transparent(true)
+    .skip_taskbar(true)
+    .visible(false)
+    .build()?;
+    cover_monitor(&win, monitor)?;
+
+    // In strict mode, raise above the menu bar / top panel so the user can't
+    // easily click away. Otherwise leave at normal always-on-top level.
+    // Done before showing so Linux can use layer-shell.
+    #[cfg(any(target_os = "macos", target_os = "linux"))]
+    {
+        if strict {
+            crate::platform::set_above_menu_bar(&win);
+            #[cfg(target_os = "linux")]
+            crate::platform::set_layer_monitor(&win, monitor);
+        }
+    }
+    #[cfg(not(any(target_os = "macos", target_os = "linux")))]
+    let _ = strict;
+    win.show()?;
+    Ok(win)
+}
+
+/// Open a full-screen eye break overlay on every monitor, or focus the
+/// overlays if a break is already showing.
+pub fn open_eye_break(
+    handle: &AppHandle,
+    duration: u32,
+    strict: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some((_, win)) = eye_break_windows(handle).into_iter().next() {
+        win.set_focus()?;
+        return Ok(());
+    }
+
+    // Pass duration + strict to the overlay via URL query params
+    let url = format!("eyebreak?duration={}&strict={}", duration, strict);
+    *EYE_BREAK.lock().unwrap() = Some((url.clone(), strict));
+
+    for (i, monitor) in handle.available_monitors()?.iter().enumerate() {
+        let win = build_eye_break(handle, i, monitor, &url, strict)?;
+        if i == 0 {
+            #[cfg(any(target_os = "macos", target_os = "linux"))]
+            if strict {
+                crate::platform::activate_app_for_input(&win);
+            }
+            win.set_focus()?;
+        }
+    }
+    lifecycle::notify(
+        handle,
+        LifecycleEvent::EyeBreakStart,
+        &crate::timer::state(),
+    );
+    Ok(())
+}
+
+/// Close the eye break overlay on every monitor.
+pub fn close_eye_break(handle: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
+    let was_open = EYE_BREAK.lock().unwrap().take().is_some();
+    for (_, win) in eye_break_windows(handle) {
+        win.close()?;
+    }
+    if was_open {
+        lifecycle::notify(handle, LifecycleEvent::EyeBreakEnd, &crate::timer::state());
+    }
+    Ok(())
+}
+
+/// Match the open overlays to the current monitors: move each onto its
+/// monitor, close those whose monitor is gone, and add overlays for new
+/// monitors. A new overlay starts its own countdown; the break still ends for
+/// all of them together.
+fn layout_eye_breaks(handle: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
+    let Some((url, strict)) = EYE_BREAK.lock().unwrap().clone() else {
+        return Ok(());
+    };
+    let monitors = handle.available_monitors()?;
+    let open = eye_break_windows(handle);
+    for (index, win) in &open {
+        match monitors.get(*index) {
+            Some(monitor) => cover_monitor(win, monitor)?,
+            None => win.close()?,
+        }
+    }
+    for (index, monitor) in monitors.iter().enumerate() {
+        if !open.iter().any(|(i, _)| *i == index) {
+            build_eye_break(handle, index, monitor, &url, strict)?;
+        }
+    }
+    Ok(())
+}